@@ -51,17 +51,28 @@ where
     let writer = BufWriter::new(file);
     let mut encoder = GifEncoder::new(writer);
     encoder.set_repeat(Repeat::Infinite)?;
-    matrix.par_build(&pos, Default::default())?;
+    matrix.par_build(pos, Default::default())?;
     for offset in (0..period).step_by(speed as usize) {
         for (iter, rgba) in matrix.values().zip(frame.buffer_mut().pixels_mut()) {
             let rgb = paint(*iter, offset);
-            *rgba = Rgba::from([rgb.r, rgb.g, rgb.b, 255]);
+            *rgba = Rgba(rgb.with_alpha(u8::MAX).into());
         }
         encoder.encode_frame(frame.clone())?;
     }
     Ok(())
 }
 
+fn palette_paint<C>(color_map: C, period: u32) -> impl FnMut(Iteration, u32) -> Rgb + Send + Clone
+where
+    C: ColorMap,
+{
+    let table = ColorTable::from_colormap(&color_map, period as usize);
+    move |iter, offset| match iter {
+        Iteration::Finite(iter) => table.cycled(offset).get(iter),
+        Iteration::Infinite => Rgb::BLACK,
+    }
+}
+
 fn main() {
     std::fs::create_dir_all("./examples/out").unwrap();
     let path = "./examples/out/animation.gif";
@@ -69,14 +80,6 @@ fn main() {
     let pos = Positions::JuliaIsland.pos();
     let period = 256;
     let speed = 3;
-    let palette = Palette::ElectricBlue;
-    let paint = move |iter, offset| match iter {
-        Iteration::Finite(iter) => {
-            let index = ((iter + offset) % period) as u8;
-            let color = palette.get_color(index);
-            color
-        }
-        Iteration::Infinite => Rgb::BLACK,
-    };
+    let paint = palette_paint(Palette::ElectricBlue, period);
     save_animation(path, width, height, pos, paint, period, speed).unwrap();
 }
@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use image::{ImageFormat, ImageResult};
+
+use crate::paint::{RgbImage, RgbaImage};
+
+impl RgbImage {
+    pub fn to_image_buffer(&self) -> image::RgbImage {
+        let data = self
+            .values()
+            .flat_map(|rgb| [rgb.r, rgb.g, rgb.b])
+            .collect();
+        image::RgbImage::from_raw(self.width(), self.height(), data).unwrap()
+    }
+
+    pub fn save_png<P>(&self, path: P) -> ImageResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        self.to_image_buffer()
+            .save_with_format(path, ImageFormat::Png)
+    }
+}
+
+impl RgbaImage {
+    pub fn to_image_buffer(&self) -> image::RgbaImage {
+        let data = self
+            .values()
+            .flat_map(|&rgba| <[u8; 4]>::from(rgba))
+            .collect();
+        image::RgbaImage::from_raw(self.width(), self.height(), data).unwrap()
+    }
+
+    pub fn save_png<P>(&self, path: P) -> ImageResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        self.to_image_buffer()
+            .save_with_format(path, ImageFormat::Png)
+    }
+}
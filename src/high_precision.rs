@@ -0,0 +1,197 @@
+use std::ops::{Add, Mul, Sub};
+
+use num::{BigInt, Float, ToPrimitive, Zero};
+
+use crate::{
+    mandelbrot::{Iteration, Position},
+    point::Point,
+};
+
+/// Binary fixed-point number: `value / 2^precision`.
+///
+/// Arithmetic between numbers of different precision first rescales both
+/// operands to the larger precision, so the result never loses bits of the
+/// more precise operand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigFixed {
+    value: BigInt,
+    precision: u32,
+}
+
+impl BigFixed {
+    pub fn zero(precision: u32) -> Self {
+        Self {
+            value: BigInt::zero(),
+            precision,
+        }
+    }
+
+    pub fn from_f64(value: f64, precision: u32) -> Self {
+        let (mantissa, exponent, sign) = value.integer_decode();
+        let mantissa = BigInt::from(mantissa) * sign;
+        let shift = exponent as i64 + precision as i64;
+        let value = if shift >= 0 {
+            mantissa << shift as u64
+        } else {
+            mantissa >> (-shift) as u64
+        };
+        Self { value, precision }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let bits = self.value.bits();
+        let shift = bits.saturating_sub(f64::MANTISSA_DIGITS as u64);
+        let mantissa = (&self.value >> shift).to_f64().unwrap_or_default();
+        mantissa * 2f64.powi(shift as i32 - self.precision as i32)
+    }
+
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    /// Returns the same number with `precision` fractional bits, truncating
+    /// towards negative infinity when the precision is lowered.
+    pub fn with_precision(&self, precision: u32) -> Self {
+        let value = if precision >= self.precision {
+            &self.value << (precision - self.precision) as u64
+        } else {
+            &self.value >> (self.precision - precision) as u64
+        };
+        Self { value, precision }
+    }
+
+    fn aligned(&self, rhs: &Self) -> (BigInt, BigInt, u32) {
+        let precision = self.precision.max(rhs.precision);
+        (
+            self.with_precision(precision).value,
+            rhs.with_precision(precision).value,
+            precision,
+        )
+    }
+}
+
+impl Add for &BigFixed {
+    type Output = BigFixed;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (lhs, rhs, precision) = self.aligned(rhs);
+        BigFixed {
+            value: lhs + rhs,
+            precision,
+        }
+    }
+}
+
+impl Sub for &BigFixed {
+    type Output = BigFixed;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let (lhs, rhs, precision) = self.aligned(rhs);
+        BigFixed {
+            value: lhs - rhs,
+            precision,
+        }
+    }
+}
+
+impl Mul for &BigFixed {
+    type Output = BigFixed;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (lhs, rhs, precision) = self.aligned(rhs);
+        BigFixed {
+            value: (lhs * rhs) >> precision as u64,
+            precision,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplexHP {
+    pub re: BigFixed,
+    pub im: BigFixed,
+}
+
+impl ComplexHP {
+    pub const fn new(re: BigFixed, im: BigFixed) -> Self {
+        Self { re, im }
+    }
+
+    pub fn compute_iterations(&self, limit: u32) -> Iteration {
+        let precision = self.re.precision();
+        let escape_radius_sq = BigFixed::from_f64(4.0, precision).value;
+        let mut z_re = self.re.clone();
+        let mut z_im = self.im.clone();
+        for i in 0..limit {
+            let sq_re = &z_re * &z_re;
+            let sq_im = &z_im * &z_im;
+            if (&sq_re.value + &sq_im.value) > escape_radius_sq {
+                return Iteration::Finite(i);
+            }
+            let re_im = &z_re * &z_im;
+            z_im = &(&re_im + &re_im) + &self.im;
+            z_re = &(&sq_re - &sq_im) + &self.re;
+        }
+        Iteration::Infinite
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionHP {
+    pub point: Point<BigFixed>,
+    pub zoom: f64,
+    pub limit: u32,
+}
+
+impl PositionHP {
+    pub const fn new(point: Point<BigFixed>, zoom: f64, limit: u32) -> Self {
+        Self { point, zoom, limit }
+    }
+
+    pub fn from_position(pos: &Position, precision: u32) -> Self {
+        let point = pos.point.transform(|v| BigFixed::from_f64(v, precision));
+        Self::new(point, pos.zoom, pos.limit)
+    }
+
+    pub fn as_complex(&self) -> ComplexHP {
+        ComplexHP::new(self.point.x.clone(), self.point.y.clone())
+    }
+
+    pub fn as_complex_with_offset(&self, offset_scale: Point<f64>) -> ComplexHP {
+        let precision = self.point.x.precision();
+        let offset = (offset_scale / self.zoom).transform(|v| BigFixed::from_f64(v, precision));
+        ComplexHP::new(&self.point.x + &offset.x, &self.point.y + &offset.y)
+    }
+
+    pub fn compute_iterations(&self, offset_scale: Point<f64>) -> Iteration {
+        self.as_complex_with_offset(offset_scale)
+            .compute_iterations(self.limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_precision_arithmetic_uses_larger_precision() {
+        let a = BigFixed::from_f64(1.5, 8);
+        let b = BigFixed::from_f64(0.25, 80);
+        for (result, expected) in [(&a + &b, 1.75), (&a - &b, 1.25), (&a * &b, 0.375)] {
+            assert_eq!(result.precision(), 80);
+            assert_eq!(result.to_f64(), expected);
+        }
+        assert_eq!(&b + &a, &a + &b);
+        assert_eq!(&b * &a, &a * &b);
+        let tiny = BigFixed::from_f64(2f64.powi(-60), 80);
+        assert_eq!(&(&a + &tiny) - &a, tiny);
+    }
+
+    #[test]
+    fn with_precision_rescales() {
+        let value = BigFixed::from_f64(-2.75, 4);
+        assert_eq!(value.with_precision(64).to_f64(), -2.75);
+        assert_eq!(value.with_precision(64).with_precision(4), value);
+        assert_eq!(value.with_precision(1).to_f64(), -3.0);
+    }
+}
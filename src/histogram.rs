@@ -0,0 +1,66 @@
+use crate::{
+    mandelbrot::{Iteration, IterationMatrix},
+    matrix::VecMatrix,
+};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistogramEqualization {
+    cdf: Vec<f64>,
+}
+
+impl HistogramEqualization {
+    pub fn new(matrix: &IterationMatrix) -> Self {
+        let mut counts = Vec::new();
+        for iter in matrix.values() {
+            if let Iteration::Finite(iter) = *iter {
+                let index = iter as usize;
+                if index >= counts.len() {
+                    counts.resize(index + 1, 0u64);
+                }
+                counts[index] += 1;
+            }
+        }
+        let total = counts.iter().sum::<u64>() as f64;
+        let mut acc = 0;
+        let cdf = counts
+            .into_iter()
+            .map(|count| {
+                acc += count;
+                acc as f64 / total
+            })
+            .collect();
+        Self { cdf }
+    }
+
+    pub fn equalize(&self, iter: Iteration) -> Option<f64> {
+        match iter {
+            Iteration::Finite(iter) => {
+                let value = self.cdf.get(iter as usize).or(self.cdf.last());
+                Some(value.copied().unwrap_or_default())
+            }
+            Iteration::Infinite => None,
+        }
+    }
+}
+
+pub fn histogram_equalize(matrix: &IterationMatrix) -> impl Fn(Iteration) -> Option<f64> + Clone {
+    let equalization = HistogramEqualization::new(matrix);
+    move |iter| equalization.equalize(iter)
+}
+
+impl IterationMatrix {
+    /// Scales finite iterations into `[0, 1]` by the largest finite iteration
+    /// present. `Infinite` cells become `-1.0`.
+    pub fn normalized(&self) -> VecMatrix<f64> {
+        let max = self
+            .values()
+            .filter_map(|iter| Option::<u32>::from(*iter))
+            .max()
+            .unwrap_or_default();
+        self.map(|iter| match *iter {
+            Iteration::Finite(_) if max == 0 => 0.0,
+            Iteration::Finite(iter) => iter as f64 / max as f64,
+            Iteration::Infinite => -1.0,
+        })
+    }
+}
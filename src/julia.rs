@@ -0,0 +1,324 @@
+use std::ops::{Deref, DerefMut};
+
+use num::complex::Complex64;
+
+use crate::{
+    mandelbrot::{
+        build_iteration_matrix_with, is_valid_smooth, par_build_iteration_matrix_with,
+        BuildMandelbrotSetOptions, GetCloser, Iteration, ParallelBuildMandelbrotSetOptions,
+        Position,
+    },
+    matrix::Matrix,
+    point::Point,
+    utils::{CancelToken, PipelineResult, Progress},
+};
+
+pub trait JuliaComplex {
+    fn compute_julia_iterations(&self, c: Complex64, limit: u32) -> Iteration;
+}
+
+impl JuliaComplex for Complex64 {
+    fn compute_julia_iterations(&self, c: Complex64, limit: u32) -> Iteration {
+        let mut z_re = self.re;
+        let mut z_im = self.im;
+        for i in 0..limit {
+            let sq_re = z_re * z_re;
+            let sq_im = z_im * z_im;
+            if (sq_re + sq_im) > 4.0 {
+                return Iteration::Finite(i);
+            }
+            z_im = 2.0 * z_re * z_im + c.im;
+            z_re = sq_re - sq_im + c.re;
+        }
+        Iteration::Infinite
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildJuliaSetOptions {
+    pub c: Complex64,
+    pub viewport_offset_scale: Option<Point<f64>>,
+    pub smooth: Option<Point<u32>>,
+    pub quick_fill: Option<bool>,
+}
+
+impl BuildJuliaSetOptions {
+    pub fn new(c: Complex64) -> Self {
+        Self {
+            c,
+            ..Default::default()
+        }
+    }
+
+    pub fn viewport_offset_scale(mut self, viewport_offset_scale: Point<f64>) -> Self {
+        self.viewport_offset_scale = Some(viewport_offset_scale);
+        self
+    }
+
+    /// A zero step on either axis disables smoothing.
+    pub fn smooth(mut self, smooth: Point<u32>) -> Self {
+        self.smooth = Some(smooth).filter(is_valid_smooth);
+        self
+    }
+
+    /// See [`BuildMandelbrotSetOptions::quick_fill`].
+    pub fn quick_fill(mut self, quick_fill: bool) -> Self {
+        self.quick_fill = Some(quick_fill);
+        self
+    }
+}
+
+pub trait JuliaSet {
+    fn build_julia(self, pos: &Position, options: BuildJuliaSetOptions);
+}
+
+pub trait JuliaSetImage<T> {
+    fn build_julia_image<F>(self, pos: &Position, convert: F, options: BuildJuliaSetOptions)
+    where
+        F: FnMut(Iteration) -> T;
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParallelBuildJuliaSetOptions {
+    pub c: Complex64,
+    pub viewport_offset_scale: Option<Point<f64>>,
+    pub smooth: Option<Point<u32>>,
+    pub quick_fill: Option<bool>,
+    pub workers: Option<u32>,
+    pub progress: Option<Progress>,
+    pub cancel: Option<CancelToken>,
+}
+
+impl ParallelBuildJuliaSetOptions {
+    pub fn new(c: Complex64) -> Self {
+        Self {
+            c,
+            ..Default::default()
+        }
+    }
+
+    pub fn viewport_offset_scale(mut self, viewport_offset_scale: Point<f64>) -> Self {
+        self.viewport_offset_scale = Some(viewport_offset_scale);
+        self
+    }
+
+    /// A zero step on either axis disables smoothing.
+    pub fn smooth(mut self, smooth: Point<u32>) -> Self {
+        self.smooth = Some(smooth).filter(is_valid_smooth);
+        self
+    }
+
+    /// See [`BuildMandelbrotSetOptions::quick_fill`].
+    pub fn quick_fill(mut self, quick_fill: bool) -> Self {
+        self.quick_fill = Some(quick_fill);
+        self
+    }
+
+    pub fn workers(mut self, workers: u32) -> Self {
+        self.workers = Some(workers);
+        self
+    }
+
+    pub fn progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        self.progress = Some(Progress::new(progress));
+        self
+    }
+
+    pub fn cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+}
+
+pub trait ParallelJuliaSet {
+    fn par_build_julia(
+        self,
+        pos: &Position,
+        options: ParallelBuildJuliaSetOptions,
+    ) -> PipelineResult<()>;
+}
+
+pub trait ParallelJuliaSetImage<T> {
+    fn par_build_julia_image<F>(
+        self,
+        pos: &Position,
+        convert: F,
+        options: ParallelBuildJuliaSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration) -> T + Send + Clone;
+}
+
+impl<T> JuliaSet for T
+where
+    T: JuliaSetImage<Iteration>,
+{
+    fn build_julia(self, pos: &Position, options: BuildJuliaSetOptions) {
+        self.build_julia_image(pos, |iter| iter, options)
+    }
+}
+
+impl<T> ParallelJuliaSet for T
+where
+    T: ParallelJuliaSetImage<Iteration>,
+{
+    fn par_build_julia(
+        self,
+        pos: &Position,
+        options: ParallelBuildJuliaSetOptions,
+    ) -> PipelineResult<()> {
+        self.par_build_julia_image(pos, |iter| iter, options)
+    }
+}
+
+impl<T, V> JuliaSetImage<T> for &mut Matrix<T, V>
+where
+    T: Clone,
+    V: Deref<Target = [T]> + DerefMut,
+{
+    fn build_julia_image<F>(self, pos: &Position, convert: F, options: BuildJuliaSetOptions)
+    where
+        F: FnMut(Iteration) -> T,
+    {
+        let BuildJuliaSetOptions {
+            c,
+            viewport_offset_scale,
+            smooth,
+            quick_fill,
+        } = options;
+        let limit = pos.limit;
+        let options = BuildMandelbrotSetOptions {
+            viewport_offset_scale,
+            smooth,
+            quick_fill,
+            ..Default::default()
+        };
+        build_iteration_matrix_with(
+            self,
+            pos,
+            move |z| z.compute_julia_iterations(c, limit),
+            convert,
+            options,
+        )
+    }
+}
+
+impl<T, V> ParallelJuliaSetImage<T> for &mut Matrix<T, V>
+where
+    T: Send + Clone,
+    V: Deref<Target = [T]> + DerefMut,
+{
+    fn par_build_julia_image<F>(
+        self,
+        pos: &Position,
+        convert: F,
+        options: ParallelBuildJuliaSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration) -> T + Send + Clone,
+    {
+        let ParallelBuildJuliaSetOptions {
+            c,
+            viewport_offset_scale,
+            smooth,
+            quick_fill,
+            workers,
+            progress,
+            cancel,
+        } = options;
+        let limit = pos.limit;
+        let options = ParallelBuildMandelbrotSetOptions {
+            viewport_offset_scale,
+            smooth,
+            quick_fill,
+            workers,
+            progress,
+            cancel,
+            ..Default::default()
+        };
+        par_build_iteration_matrix_with(
+            self,
+            pos,
+            move |z| z.compute_julia_iterations(c, limit),
+            convert,
+            options,
+        )
+    }
+}
+
+/// Steps the Julia constant toward a target along a straight line, or around
+/// a closed loop of waypoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JuliaController {
+    pub c: Complex64,
+    /// Distance travelled per step.
+    pub speed: f64,
+    pub waypoints: Vec<Complex64>,
+    pub waypoint: usize,
+}
+
+impl JuliaController {
+    pub fn new(c: Complex64) -> Self {
+        Self {
+            c,
+            ..Default::default()
+        }
+    }
+
+    pub fn make_step(&mut self, to: Complex64) -> bool {
+        let from = Point::new(self.c.re, self.c.im);
+        let delta = Point::new(to.re, to.im) - from;
+        let distance = delta.length();
+        if distance <= self.speed {
+            self.c = to;
+            return true;
+        }
+        let step = delta.transform(f64::abs) * (self.speed / distance);
+        let (c, reached) = from.get_closer(Point::new(to.re, to.im), step);
+        self.c = c.into();
+        reached
+    }
+
+    /// Steps toward the current waypoint, moving on to the next one, and back
+    /// to the first after the last, once it is reached.
+    pub fn next_c(&mut self) -> Complex64 {
+        if let Some(&to) = self.waypoints.get(self.waypoint) {
+            if self.make_step(to) {
+                self.waypoint = (self.waypoint + 1) % self.waypoints.len();
+            }
+        }
+        self.c
+    }
+}
+
+impl Default for JuliaController {
+    fn default() -> Self {
+        Self {
+            c: Complex64::default(),
+            speed: 0.01,
+            waypoints: Vec::new(),
+            waypoint: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mandelbrot::IterationMatrix;
+
+    #[test]
+    fn julia_quick_fill_matches_full_build() {
+        let c = Complex64::new(-0.4, 0.1);
+        let pos = Position::new(Point::new(0.0, 0.0), 2000.0, 100);
+        let mut expected = IterationMatrix::new(32, 32);
+        expected.build_julia(&pos, BuildJuliaSetOptions::new(c));
+        let mut actual = IterationMatrix::new(32, 32);
+        actual.build_julia(&pos, BuildJuliaSetOptions::new(c).quick_fill(true));
+        assert!(expected.values().all(Iteration::is_infinite));
+        assert!(actual == expected);
+    }
+}
@@ -1,13 +1,33 @@
+#[cfg(feature = "image")]
+mod export;
+#[cfg(feature = "high-precision")]
+mod high_precision;
+mod histogram;
+mod julia;
 mod mandelbrot;
 mod matrix;
 mod paint;
+#[cfg(feature = "high-precision")]
+mod perturbation;
 mod point;
+mod positions;
+mod storage;
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils;
+mod travel;
 mod utils;
 
 pub use crate::{
+    histogram::*,
+    julia::*,
     mandelbrot::*,
     matrix::*,
     paint::*,
     point::*,
-    utils::{PipelineError, PipelineResult},
+    positions::*,
+    travel::*,
+    utils::{recommended_workers, CancelToken, Cancelled, PipelineError, PipelineResult, Progress},
 };
+
+#[cfg(feature = "high-precision")]
+pub use crate::{high_precision::*, perturbation::*};
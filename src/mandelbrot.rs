@@ -1,23 +1,69 @@
 use std::{
     cmp::Ordering,
+    error::Error,
+    f64::consts::LN_2,
+    fmt::{self, Display, Formatter},
     ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
-use num::{complex::Complex64, Complex};
+use num::{
+    complex::{Complex32, Complex64},
+    Complex, Float,
+};
 
 use crate::{
+    julia::JuliaComplex,
     matrix::{Matrix, VecMatrix},
-    point::Point,
-    utils::{pipeline, CrossJoin, Duplicate, PipelineResult, TupleMapper},
+    paint::{Rgb, Rgba},
+    point::{Lerp, Point},
+    positions::Positions,
+    travel::Easing,
+    utils::{CancelToken, CrossJoin, Duplicate, PipelineResult, Progress, TupleMapper},
 };
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg(not(feature = "rayon"))]
+use crate::utils::pipeline;
+
+/// Ordered so that `Infinite` is greater than any `Finite` count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Iteration {
     Finite(u32),
     #[default]
     Infinite,
 }
 
+impl Iteration {
+    pub fn is_infinite(&self) -> bool {
+        matches!(self, Iteration::Infinite)
+    }
+
+    /// Whether the point escaped within `margin` iterations of `limit`, which
+    /// hints that a higher limit could change nearby pixels. Interior points
+    /// are never near the limit.
+    pub fn is_near_limit(&self, limit: u32, margin: u32) -> bool {
+        match *self {
+            Iteration::Finite(iter) => iter.saturating_add(margin) >= limit,
+            Iteration::Infinite => false,
+        }
+    }
+
+    pub fn unwrap_or(self, default: u32) -> u32 {
+        match self {
+            Iteration::Finite(iter) => iter,
+            Iteration::Infinite => default,
+        }
+    }
+
+    pub fn map(self, f: impl FnOnce(u32) -> u32) -> Iteration {
+        match self {
+            Iteration::Finite(iter) => Iteration::Finite(f(iter)),
+            Iteration::Infinite => Iteration::Infinite,
+        }
+    }
+}
+
 impl From<Iteration> for Option<u32> {
     fn from(value: Iteration) -> Self {
         match value {
@@ -27,492 +73,2705 @@ impl From<Iteration> for Option<u32> {
     }
 }
 
-pub trait MandelbrotComplex {
-    fn compute_iterations(&self, limit: u32) -> Iteration;
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SmoothIteration {
+    Finite(f64),
+    #[default]
+    Infinite,
 }
 
-impl MandelbrotComplex for Complex64 {
-    fn compute_iterations(&self, limit: u32) -> Iteration {
-        let Self { re, im } = *self;
-        if re > -0.5 && re < 0.25 && im > -0.5 && im < 0.5 {
-            return Iteration::Infinite;
+impl SmoothIteration {
+    pub fn is_infinite(&self) -> bool {
+        matches!(self, SmoothIteration::Infinite)
+    }
+
+    pub fn unwrap_or(self, default: f64) -> f64 {
+        match self {
+            SmoothIteration::Finite(iter) => iter,
+            SmoothIteration::Infinite => default,
         }
-        let mut z_re = re;
-        let mut z_im = im;
-        for i in 0..limit {
-            let sq_re = z_re * z_re;
-            let sq_im = z_im * z_im;
-            if (sq_re + sq_im) > 4.0 {
-                return Iteration::Finite(i);
-            }
-            z_im = 2.0 * z_re * z_im + im;
-            z_re = sq_re - sq_im + re;
+    }
+
+    pub fn map(self, f: impl FnOnce(f64) -> f64) -> SmoothIteration {
+        match self {
+            SmoothIteration::Finite(iter) => SmoothIteration::Finite(f(iter)),
+            SmoothIteration::Infinite => SmoothIteration::Infinite,
         }
-        return Iteration::Infinite;
     }
 }
 
-impl<T> From<Point<T>> for Complex<T> {
-    fn from(value: Point<T>) -> Self {
-        Self::new(value.x, value.y)
+impl From<Iteration> for SmoothIteration {
+    fn from(value: Iteration) -> Self {
+        match value {
+            Iteration::Finite(iter) => SmoothIteration::Finite(iter as f64),
+            Iteration::Infinite => SmoothIteration::Infinite,
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Position {
-    pub point: Point<f64>,
-    pub zoom: f64,
-    pub limit: u32,
+/// Floors the smooth count, saturating negative values to zero.
+impl From<SmoothIteration> for Iteration {
+    fn from(value: SmoothIteration) -> Self {
+        match value {
+            SmoothIteration::Finite(iter) => Iteration::Finite(iter.floor() as u32),
+            SmoothIteration::Infinite => Iteration::Infinite,
+        }
+    }
 }
 
-impl Position {
-    pub const fn new(point: Point<f64>, zoom: f64, limit: u32) -> Self {
-        Self { point, zoom, limit }
+impl From<SmoothIteration> for Option<f64> {
+    fn from(value: SmoothIteration) -> Self {
+        match value {
+            SmoothIteration::Finite(iter) => Some(iter),
+            SmoothIteration::Infinite => None,
+        }
     }
+}
 
-    pub fn left(&mut self, offset_scale: f64) {
-        self.point.x -= offset_scale / self.zoom;
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrbitTrap {
+    Point(Complex64),
+    Line { point: Complex64, angle: f64 },
+    Circle { center: Complex64, radius: f64 },
+}
 
-    pub fn right(&mut self, offset_scale: f64) {
-        self.point.x += offset_scale / self.zoom;
+impl OrbitTrap {
+    pub fn distance(&self, z: Complex64) -> f64 {
+        match *self {
+            OrbitTrap::Point(point) => (z - point).norm(),
+            OrbitTrap::Line { point, angle } => {
+                let rotated = (z - point) * Complex64::from_polar(1.0, -angle);
+                rotated.im.abs()
+            }
+            OrbitTrap::Circle { center, radius } => ((z - center).norm() - radius).abs(),
+        }
     }
+}
 
-    pub fn up(&mut self, offset_scale: f64) {
-        self.point.y += offset_scale / self.zoom;
-    }
+const ESCAPE_RADIUS_SQ: f64 = 4.0;
 
-    pub fn down(&mut self, offset_scale: f64) {
-        self.point.y -= offset_scale / self.zoom;
+const NORMAL_ESCAPE_RADIUS_SQ: f64 = 10000.0;
+
+const STRIPE_ESCAPE_RADIUS_SQ: f64 = 1e10;
+
+const PERIOD_EPSILON: f64 = 1e-12;
+
+pub trait MandelbrotComplex {
+    fn compute_iterations(&self, limit: u32) -> Iteration;
+
+    fn compute_iterations_with_radius(&self, limit: u32, escape_radius_sq: f64) -> Iteration;
+
+    fn compute_iterations_with_check(&self, limit: u32, interior_check: InteriorCheck)
+        -> Iteration;
+
+    fn compute_iterations_with_z(&self, limit: u32) -> (Iteration, Complex64);
+
+    fn compute_iterations_periodic(&self, limit: u32) -> Iteration;
+
+    fn compute_multibrot_iterations(&self, limit: u32, power: u32) -> Iteration;
+
+    fn compute_tricorn_iterations(&self, limit: u32) -> Iteration;
+
+    fn compute_smooth_iterations(&self, limit: u32) -> SmoothIteration;
+
+    fn compute_distance_estimate(&self, limit: u32, escape_radius_sq: f64) -> Option<f64>;
+
+    fn compute_orbit_trap(&self, limit: u32, trap: &OrbitTrap) -> f64;
+
+    fn compute_normal(&self, limit: u32) -> Option<Point<f64>>;
+
+    /// Average of `0.5 * sin(k * arg(z)) + 0.5` over the orbit, blended
+    /// between the last two averages by the fractional escape count. Lies in
+    /// `[0, 1]`, `None` for points that do not escape.
+    fn compute_stripe_average(&self, limit: u32, k: f64) -> Option<f64>;
+}
+
+impl MandelbrotComplex for Complex64 {
+    fn compute_iterations(&self, limit: u32) -> Iteration {
+        mandelbrot_escape(self.re, self.im, limit)
     }
 
-    pub fn translate(&mut self, offset_scale: Point<f64>) {
-        self.point += offset_scale / self.zoom;
+    fn compute_iterations_with_radius(&self, limit: u32, escape_radius_sq: f64) -> Iteration {
+        let (iter, _) = iterate(*self, limit, escape_radius_sq);
+        iter
     }
 
-    pub fn change_zoom(&mut self, zoom_scale: f64) {
-        self.zoom += self.zoom * zoom_scale;
+    fn compute_iterations_with_check(
+        &self,
+        limit: u32,
+        interior_check: InteriorCheck,
+    ) -> Iteration {
+        let (iter, _) = iterate_with_check(*self, limit, ESCAPE_RADIUS_SQ, interior_check);
+        iter
     }
 
-    pub fn update_limit(&mut self, limit_scale: f64) {
-        self.limit = (self.zoom * limit_scale) as u32;
+    fn compute_iterations_with_z(&self, limit: u32) -> (Iteration, Complex64) {
+        iterate(*self, limit, ESCAPE_RADIUS_SQ)
     }
 
-    pub fn clamp_zoom(&mut self, min: f64, max: f64) {
-        self.zoom = self.zoom.clamp(min, max);
+    fn compute_iterations_periodic(&self, limit: u32) -> Iteration {
+        periodic_escape(*self, limit)
     }
 
-    pub fn clamp_limit(&mut self, min: u32, max: u32) {
-        self.limit = self.limit.clamp(min, max);
+    fn compute_multibrot_iterations(&self, limit: u32, power: u32) -> Iteration {
+        if power == 2 {
+            return self.compute_iterations(limit);
+        }
+        multibrot_escape(*self, limit, power)
     }
 
-    pub fn as_complex(&self) -> Complex64 {
-        Complex64::from(self.point)
+    fn compute_tricorn_iterations(&self, limit: u32) -> Iteration {
+        tricorn_escape(*self, limit)
     }
 
-    pub fn as_complex_with_offset(&self, offset_scale: Point<f64>) -> Complex64 {
-        Complex::from(self.point + offset_scale / self.zoom)
+    fn compute_smooth_iterations(&self, limit: u32) -> SmoothIteration {
+        let (iter, z) = self.compute_iterations_with_z(limit);
+        smooth_iteration(iter, z)
     }
 
-    pub fn make_step(
-        &mut self,
-        to: &Position,
-        offset_scale: Point<f64>,
-        zoom_scale: f64,
-        limit_scale: f64,
-    ) -> bool {
-        if self.zoom < to.zoom {
-            self.make_step_point(to, offset_scale)
-                && self.make_step_zoom_and_limit(to, zoom_scale, limit_scale)
-        } else {
-            self.make_step_zoom_and_limit(to, zoom_scale, limit_scale)
-                && self.make_step_point(to, offset_scale)
-        }
+    fn compute_distance_estimate(&self, limit: u32, escape_radius_sq: f64) -> Option<f64> {
+        distance_estimate(*self, limit, escape_radius_sq)
     }
 
-    fn make_step_point(&mut self, to: &Position, offset_scale: Point<f64>) -> bool {
-        let (point, reached) = self.point.get_closer(to.point, offset_scale / self.zoom);
-        self.point = point;
-        reached
+    fn compute_orbit_trap(&self, limit: u32, trap: &OrbitTrap) -> f64 {
+        orbit_trap(*self, limit, trap)
     }
 
-    fn make_step_zoom_and_limit(
-        &mut self,
-        to: &Position,
-        zoom_scale: f64,
-        limit_scale: f64,
-    ) -> bool {
-        let (zoom, reached) = self.zoom.get_closer(to.zoom, self.zoom * zoom_scale);
-        self.zoom = zoom;
-        if reached {
-            self.limit = to.limit;
-        } else if self.limit != to.limit {
-            let limit_step = ((self.limit as f64 * zoom_scale * limit_scale) as u32).max(1);
-            let (limit, _) = self.limit.get_closer(to.limit, limit_step);
-            self.limit = limit;
-        }
-        reached
+    fn compute_normal(&self, limit: u32) -> Option<Point<f64>> {
+        normal(*self, limit)
     }
-}
 
-impl Default for Position {
-    fn default() -> Self {
-        Positions::Home.pos().clone()
+    fn compute_stripe_average(&self, limit: u32, k: f64) -> Option<f64> {
+        stripe_average(*self, limit, k)
     }
 }
 
-trait GetCloser<T = Self, S = Self> {
-    type Output;
-
-    fn get_closer(self, to: T, step: S) -> (Self::Output, bool);
+pub fn mandelbrot_escape(re: f64, im: f64, limit: u32) -> Iteration {
+    let (iter, _) = iterate(Complex64::new(re, im), limit, ESCAPE_RADIUS_SQ);
+    iter
 }
 
-impl GetCloser for u32 {
-    type Output = Self;
+fn iterate<T>(c: Complex<T>, limit: u32, escape_radius_sq: T) -> (Iteration, Complex<T>)
+where
+    T: Float,
+{
+    iterate_with_check(c, limit, escape_radius_sq, InteriorCheck::Cardioid)
+}
 
-    fn get_closer(self, to: Self, step: Self) -> (Self::Output, bool) {
-        match self.cmp(&to) {
-            Ordering::Equal => (to, true),
-            Ordering::Less => {
-                let delta = to - self;
-                if delta < step {
-                    (to, true)
-                } else {
-                    (self + step, false)
-                }
-            }
-            Ordering::Greater => {
-                let delta = self - to;
-                if delta < step {
-                    (to, true)
-                } else {
-                    (self - step, false)
-                }
-            }
+fn iterate_with_check<T>(
+    c: Complex<T>,
+    limit: u32,
+    escape_radius_sq: T,
+    interior_check: InteriorCheck,
+) -> (Iteration, Complex<T>)
+where
+    T: Float,
+{
+    if interior_check.contains(c) {
+        return (Iteration::Infinite, c);
+    }
+    let Complex { re, im } = c;
+    let mut z_re = re;
+    let mut z_im = im;
+    for i in 0..limit {
+        let sq_re = z_re * z_re;
+        let sq_im = z_im * z_im;
+        if (sq_re + sq_im) > escape_radius_sq {
+            return (Iteration::Finite(i), Complex::new(z_re, z_im));
         }
+        z_im = (z_re + z_re) * z_im + im;
+        z_re = sq_re - sq_im + re;
     }
+    (Iteration::Infinite, Complex::new(z_re, z_im))
 }
 
-impl GetCloser for f64 {
-    type Output = Self;
+/// The iteration formula used by the builders. Only `Mandelbrot` takes the
+/// `power` and `interior_check` options into account.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FractalKind {
+    #[default]
+    Mandelbrot,
+    /// Iterates `z^2 + c` with a fixed `c`, starting from the point itself.
+    Julia {
+        c: Complex64,
+    },
+    Multibrot {
+        power: u32,
+    },
+    /// Iterates `(|re(z)| + i|im(z)|)^2 + c`.
+    BurningShip,
+    /// Iterates `conj(z)^2 + c`.
+    Tricorn,
+}
 
-    fn get_closer(self, to: Self, step: Self) -> (Self::Output, bool) {
-        match self.partial_cmp(&to) {
-            None => (to, true),
-            Some(ord) => match ord {
-                Ordering::Equal => (to, true),
-                Ordering::Less => {
-                    let delta = to - self;
-                    if delta < step {
-                        (to, true)
-                    } else {
-                        (self + step, false)
-                    }
-                }
-                Ordering::Greater => {
-                    let delta = self - to;
-                    if delta < step {
-                        (to, true)
-                    } else {
-                        (self - step, false)
-                    }
-                }
-            },
+impl FractalKind {
+    pub fn iterate(&self, point: Complex64, limit: u32) -> Iteration {
+        match *self {
+            FractalKind::Mandelbrot => point.compute_iterations(limit),
+            FractalKind::Julia { c } => point.compute_julia_iterations(c, limit),
+            FractalKind::Multibrot { power } => point.compute_multibrot_iterations(limit, power),
+            FractalKind::BurningShip => burning_ship_escape(point, limit),
+            FractalKind::Tricorn => point.compute_tricorn_iterations(limit),
         }
     }
 }
 
-impl GetCloser for Point<f64> {
-    type Output = Self;
-
-    fn get_closer(self, to: Self, step: Self) -> (Self::Output, bool) {
-        let (x, reached_x) = self.x.get_closer(to.x, step.x);
-        let (y, reached_y) = self.y.get_closer(to.y, step.y);
-        (Self::new(x, y), reached_x && reached_y)
+fn burning_ship_escape(c: Complex64, limit: u32) -> Iteration {
+    let Complex64 { re, im } = c;
+    let mut z_re = re;
+    let mut z_im = im;
+    for i in 0..limit {
+        let sq_re = z_re * z_re;
+        let sq_im = z_im * z_im;
+        if (sq_re + sq_im) > ESCAPE_RADIUS_SQ {
+            return Iteration::Finite(i);
+        }
+        z_im = (z_re * z_im).abs() * 2.0 + im;
+        z_re = sq_re - sq_im + re;
     }
+    Iteration::Infinite
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct PositionController {
-    pub pos: Position,
-    pub step: Point<f64>,
-    pub zoom_scale: f64,
-    pub min_zoom: f64,
-    pub max_zoom: f64,
-    pub limit_scale: f64,
-    pub min_limit: u32,
-    pub max_limit: u32,
+/// Shortcut that reports points known to be inside the set before iterating.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InteriorCheck {
+    None,
+    /// Exact test for the main cardioid and the period-2 bulb.
+    #[default]
+    Cardioid,
+    /// Cheaper test for rectangles inscribed in the main cardioid and the
+    /// period-2 bulb. Misses the points near their edges.
+    BoundingBox,
 }
 
-impl PositionController {
-    pub fn left(&mut self) {
-        self.pos.left(self.step.x);
-    }
-
-    pub fn right(&mut self) {
-        self.pos.right(self.step.x);
+impl InteriorCheck {
+    pub fn contains<T>(self, c: Complex<T>) -> bool
+    where
+        T: Float,
+    {
+        match self {
+            InteriorCheck::None => false,
+            InteriorCheck::Cardioid => is_interior(c),
+            InteriorCheck::BoundingBox => is_in_interior_box(c),
+        }
     }
+}
 
-    pub fn up(&mut self) {
-        self.pos.up(self.step.y);
-    }
+fn is_in_interior_box<T>(c: Complex<T>) -> bool
+where
+    T: Float,
+{
+    let Complex { re, im } = c;
+    let in_cardioid = re >= constant(-0.5) && re <= constant(0.2) && im.abs() <= constant(0.5);
+    let in_bulb = (re + T::one()).abs() <= constant(0.17) && im.abs() <= constant(0.17);
+    in_cardioid || in_bulb
+}
 
-    pub fn down(&mut self) {
-        self.pos.down(self.step.y);
-    }
+fn is_interior<T>(c: Complex<T>) -> bool
+where
+    T: Float,
+{
+    let Complex { re, im } = c;
+    let quarter = constant::<T>(0.25);
+    let sq_im = im * im;
+    let shifted_re = re - quarter;
+    let q = shifted_re * shifted_re + sq_im;
+    let in_cardioid = q * (q + shifted_re) <= quarter * sq_im;
+    let bulb_re = re + T::one();
+    let in_bulb = bulb_re * bulb_re + sq_im <= constant(0.0625);
+    in_cardioid || in_bulb
+}
 
-    pub fn translate(&mut self, offset_scale: Point<f64>) {
-        self.pos.translate(offset_scale);
+const LANES: usize = 4;
+
+/// Same as calling [`MandelbrotComplex::compute_iterations`] on every point,
+/// iterating `LANES` points in lockstep and masking off the escaped ones.
+/// The lanes are plain arrays that the compiler vectorizes on stable, as
+/// `std::simd` is nightly-only.
+pub fn compute_iterations_batch(points: &[Complex64], limit: u32, out: &mut [Iteration]) {
+    assert_eq!(
+        points.len(),
+        out.len(),
+        "Batch input and output lengths differ"
+    );
+    let mut points = points.chunks_exact(LANES);
+    let mut out = out.chunks_exact_mut(LANES);
+    for (points, out) in (&mut points).zip(&mut out) {
+        iterate_lanes(points, limit, out);
     }
-
-    pub fn change_zoom(&mut self, zoom_scale: f64) {
-        self.pos.change_zoom(zoom_scale);
-        self.clamp_zoom();
+    let remainder = points.remainder().iter();
+    for (point, dest) in remainder.zip(out.into_remainder()) {
+        *dest = point.compute_iterations(limit);
     }
+}
 
-    pub fn increase_zoom(&mut self) {
-        self.change_zoom(self.zoom_scale);
+fn iterate_lanes(points: &[Complex64], limit: u32, out: &mut [Iteration]) {
+    let mut re = [0.0; LANES];
+    let mut im = [0.0; LANES];
+    let mut active = [false; LANES];
+    for lane in 0..LANES {
+        re[lane] = points[lane].re;
+        im[lane] = points[lane].im;
+        active[lane] = !is_interior(points[lane]);
+        out[lane] = Iteration::Infinite;
     }
-
-    pub fn decrease_zoom(&mut self) {
-        self.change_zoom(-self.zoom_scale);
+    let mut z_re = re;
+    let mut z_im = im;
+    for i in 0..limit {
+        if !active.contains(&true) {
+            break;
+        }
+        for lane in 0..LANES {
+            let sq_re = z_re[lane] * z_re[lane];
+            let sq_im = z_im[lane] * z_im[lane];
+            if active[lane] && (sq_re + sq_im) > ESCAPE_RADIUS_SQ {
+                out[lane] = Iteration::Finite(i);
+                active[lane] = false;
+            }
+            z_im[lane] = (z_re[lane] + z_re[lane]) * z_im[lane] + im[lane];
+            z_re[lane] = sq_re - sq_im + re[lane];
+        }
     }
+}
 
-    pub fn update_limit(&mut self) {
-        self.pos.update_limit(self.limit_scale);
-        self.clamp_limit();
-    }
+fn constant<T>(value: f64) -> T
+where
+    T: Float,
+{
+    T::from(value).unwrap()
+}
 
-    pub fn clamp_zoom(&mut self) {
-        self.pos.clamp_zoom(self.min_zoom, self.max_zoom);
+fn periodic_escape<T>(c: Complex<T>, limit: u32) -> Iteration
+where
+    T: Float,
+{
+    if is_interior(c) {
+        return Iteration::Infinite;
     }
-
-    pub fn clamp_limit(&mut self) {
-        self.pos.clamp_limit(self.min_limit, self.max_limit);
+    let escape_radius_sq = constant::<T>(ESCAPE_RADIUS_SQ);
+    let epsilon = constant::<T>(PERIOD_EPSILON);
+    let Complex { re, im } = c;
+    let mut z_re = re;
+    let mut z_im = im;
+    let mut old_re = z_re;
+    let mut old_im = z_im;
+    let mut period = 0;
+    let mut check = 3;
+    for i in 0..limit {
+        let sq_re = z_re * z_re;
+        let sq_im = z_im * z_im;
+        if (sq_re + sq_im) > escape_radius_sq {
+            return Iteration::Finite(i);
+        }
+        z_im = constant::<T>(2.0) * z_re * z_im + im;
+        z_re = sq_re - sq_im + re;
+        if (z_re - old_re).abs() < epsilon && (z_im - old_im).abs() < epsilon {
+            return Iteration::Infinite;
+        }
+        period += 1;
+        if period > check {
+            period = 0;
+            check *= 2;
+            old_re = z_re;
+            old_im = z_im;
+        }
     }
+    Iteration::Infinite
+}
 
-    pub fn make_step(&mut self, to: &Position) -> bool {
-        self.pos
-            .make_step(to, self.step, self.zoom_scale, self.limit_scale)
+fn multibrot_escape<T>(c: Complex<T>, limit: u32, power: u32) -> Iteration
+where
+    T: Float,
+{
+    let escape_radius_sq = constant::<T>(ESCAPE_RADIUS_SQ);
+    let mut z = c;
+    for i in 0..limit {
+        if z.norm_sqr() > escape_radius_sq {
+            return Iteration::Finite(i);
+        }
+        z = (0..power).fold(Complex::new(T::one(), T::zero()), |acc, _| acc * z) + c;
     }
+    Iteration::Infinite
 }
 
-impl Default for PositionController {
-    fn default() -> Self {
-        Self {
-            pos: Position::default(),
-            step: Point::new(10.0, 10.0),
-            zoom_scale: 0.2,
-            min_zoom: 50.0,
-            max_zoom: 4500000000000000.0,
-            limit_scale: 0.25,
-            min_limit: 150,
-            max_limit: 1500,
+fn tricorn_escape<T>(c: Complex<T>, limit: u32) -> Iteration
+where
+    T: Float,
+{
+    let escape_radius_sq = constant::<T>(ESCAPE_RADIUS_SQ);
+    let Complex { re, im } = c;
+    let mut z_re = re;
+    let mut z_im = im;
+    for i in 0..limit {
+        let sq_re = z_re * z_re;
+        let sq_im = z_im * z_im;
+        if (sq_re + sq_im) > escape_radius_sq {
+            return Iteration::Finite(i);
         }
+        z_im = -(z_re + z_re) * z_im + im;
+        z_re = sq_re - sq_im + re;
     }
+    Iteration::Infinite
 }
 
-#[derive(Debug, Clone, Default, PartialEq)]
+fn distance_estimate<T>(c: Complex<T>, limit: u32, escape_radius_sq: f64) -> Option<f64>
+where
+    T: Float,
+{
+    if is_interior(c) {
+        return None;
+    }
+    let escape_radius_sq = constant::<T>(escape_radius_sq);
+    let two = constant::<T>(2.0);
+    let mut z = c;
+    let mut dz = Complex::new(T::one(), T::zero());
+    for _ in 0..limit {
+        let sq_norm = z.norm_sqr();
+        if sq_norm > escape_radius_sq {
+            let norm = sq_norm.sqrt();
+            return (two * norm * norm.ln() / dz.norm()).to_f64();
+        }
+        dz = z * dz * two + T::one();
+        z = z * z + c;
+    }
+    None
+}
+
+fn orbit_trap<T>(c: Complex<T>, limit: u32, trap: &OrbitTrap) -> f64
+where
+    T: Float,
+{
+    let escape_radius_sq = constant::<T>(ESCAPE_RADIUS_SQ);
+    let mut z = c;
+    let mut min_distance = f64::INFINITY;
+    for _ in 0..limit {
+        if z.norm_sqr() > escape_radius_sq {
+            break;
+        }
+        min_distance = min_distance.min(trap.distance(widen(z)));
+        z = z * z + c;
+    }
+    min_distance
+}
+
+fn normal<T>(c: Complex<T>, limit: u32) -> Option<Point<f64>>
+where
+    T: Float,
+{
+    if is_interior(c) {
+        return None;
+    }
+    let escape_radius_sq = constant::<T>(NORMAL_ESCAPE_RADIUS_SQ);
+    let two = constant::<T>(2.0);
+    let mut z = c;
+    let mut dz = Complex::new(T::one(), T::zero());
+    for _ in 0..limit {
+        if z.norm_sqr() > escape_radius_sq {
+            let u = z / dz;
+            let u = widen(u / u.norm());
+            return Some(Point::new(u.re, u.im));
+        }
+        dz = z * dz * two + T::one();
+        z = z * z + c;
+    }
+    None
+}
+
+fn stripe_average<T>(c: Complex<T>, limit: u32, k: f64) -> Option<f64>
+where
+    T: Float,
+{
+    let escape_radius_sq = constant::<T>(STRIPE_ESCAPE_RADIUS_SQ);
+    let (k, half) = (constant::<T>(k), constant::<T>(0.5));
+    let mut z = c;
+    let mut sum = T::zero();
+    for i in 0..limit {
+        let stripe = half * (k * z.arg()).sin() + half;
+        sum = sum + stripe;
+        if z.norm_sqr() > escape_radius_sq {
+            let count = constant::<T>((i + 1) as f64);
+            let average = sum / count;
+            let previous = match i {
+                0 => average,
+                _ => (sum - stripe) / (count - T::one()),
+            };
+            let log_norm = z.norm_sqr().ln() / constant(2.0);
+            let frac = T::one() + (escape_radius_sq.ln() / constant(2.0) / log_norm).log2();
+            return (previous + (average - previous) * frac).to_f64();
+        }
+        z = z * z + c;
+    }
+    None
+}
+
+impl MandelbrotComplex for Complex32 {
+    fn compute_iterations(&self, limit: u32) -> Iteration {
+        self.compute_iterations_with_radius(limit, ESCAPE_RADIUS_SQ)
+    }
+
+    fn compute_iterations_with_radius(&self, limit: u32, escape_radius_sq: f64) -> Iteration {
+        let (iter, _) = iterate(*self, limit, escape_radius_sq as f32);
+        iter
+    }
+
+    fn compute_iterations_with_check(
+        &self,
+        limit: u32,
+        interior_check: InteriorCheck,
+    ) -> Iteration {
+        let escape_radius_sq = ESCAPE_RADIUS_SQ as f32;
+        let (iter, _) = iterate_with_check(*self, limit, escape_radius_sq, interior_check);
+        iter
+    }
+
+    fn compute_iterations_with_z(&self, limit: u32) -> (Iteration, Complex64) {
+        let (iter, z) = iterate(*self, limit, ESCAPE_RADIUS_SQ as f32);
+        (iter, widen(z))
+    }
+
+    fn compute_iterations_periodic(&self, limit: u32) -> Iteration {
+        periodic_escape(*self, limit)
+    }
+
+    fn compute_multibrot_iterations(&self, limit: u32, power: u32) -> Iteration {
+        if power == 2 {
+            return self.compute_iterations(limit);
+        }
+        multibrot_escape(*self, limit, power)
+    }
+
+    fn compute_tricorn_iterations(&self, limit: u32) -> Iteration {
+        tricorn_escape(*self, limit)
+    }
+
+    fn compute_smooth_iterations(&self, limit: u32) -> SmoothIteration {
+        let (iter, z) = self.compute_iterations_with_z(limit);
+        smooth_iteration(iter, z)
+    }
+
+    fn compute_distance_estimate(&self, limit: u32, escape_radius_sq: f64) -> Option<f64> {
+        distance_estimate(*self, limit, escape_radius_sq)
+    }
+
+    fn compute_orbit_trap(&self, limit: u32, trap: &OrbitTrap) -> f64 {
+        orbit_trap(*self, limit, trap)
+    }
+
+    fn compute_normal(&self, limit: u32) -> Option<Point<f64>> {
+        normal(*self, limit)
+    }
+
+    fn compute_stripe_average(&self, limit: u32, k: f64) -> Option<f64> {
+        stripe_average(*self, limit, k)
+    }
+}
+
+/// Orbits are iterated in `T`, only the values handed back are widened.
+fn widen<T>(value: Complex<T>) -> Complex64
+where
+    T: Float,
+{
+    Complex64::new(value.re.to_f64().unwrap(), value.im.to_f64().unwrap())
+}
+
+impl<T> From<Point<T>> for Complex<T> {
+    fn from(value: Point<T>) -> Self {
+        Self::new(value.x, value.y)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub point: Point<f64>,
+    pub zoom: f64,
+    pub limit: u32,
+    /// Counterclockwise rotation of the viewport around `point`, in radians.
+    pub rotation: f64,
+}
+
+impl Position {
+    pub const fn new(point: Point<f64>, zoom: f64, limit: u32) -> Self {
+        Self {
+            point,
+            zoom,
+            limit,
+            rotation: 0.0,
+        }
+    }
+
+    pub const fn rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn left(&mut self, offset_scale: f64) {
+        self.translate(Point::new(-offset_scale, 0.0));
+    }
+
+    pub fn right(&mut self, offset_scale: f64) {
+        self.translate(Point::new(offset_scale, 0.0));
+    }
+
+    pub fn up(&mut self, offset_scale: f64) {
+        self.translate(Point::new(0.0, offset_scale));
+    }
+
+    pub fn down(&mut self, offset_scale: f64) {
+        self.translate(Point::new(0.0, -offset_scale));
+    }
+
+    /// Moves along the axes of the rotated viewport.
+    pub fn translate(&mut self, offset_scale: Point<f64>) {
+        self.point += offset_scale.rotate(self.rotation) / self.zoom;
+    }
+
+    /// Multiplies the zoom by `1 + zoom_scale`. `zoom_scale` must be greater
+    /// than `-1`, otherwise the zoom would stop being positive and it is left
+    /// unchanged.
+    pub fn change_zoom(&mut self, zoom_scale: f64) {
+        let _ = self.checked_change_zoom(zoom_scale);
+    }
+
+    pub fn checked_change_zoom(&mut self, zoom_scale: f64) -> Result<(), InvalidZoom> {
+        let zoom = self.zoom + self.zoom * zoom_scale;
+        if zoom > 0.0 && zoom.is_finite() {
+            self.zoom = zoom;
+            Ok(())
+        } else {
+            Err(InvalidZoom(zoom))
+        }
+    }
+
+    pub fn zoom_at(&mut self, zoom_scale: f64, focal: Complex64) {
+        let focal = Point::new(focal.re, focal.im);
+        let zoom = self.zoom;
+        self.change_zoom(zoom_scale);
+        self.point = focal - (focal - self.point) * (zoom / self.zoom);
+    }
+
+    pub fn update_limit(&mut self, limit_scale: f64) {
+        self.update_limit_with(LimitCurve::Linear, limit_scale);
+    }
+
+    pub fn update_limit_with(&mut self, curve: LimitCurve, limit_scale: f64) {
+        self.limit = (curve.apply(self.zoom) * limit_scale) as u32;
+    }
+
+    pub fn clamp_zoom(&mut self, min: f64, max: f64) {
+        self.zoom = self.zoom.clamp(min, max);
+    }
+
+    pub fn clamp_limit(&mut self, min: u32, max: u32) {
+        self.limit = self.limit.clamp(min, max);
+    }
+
+    pub fn as_complex(&self) -> Complex64 {
+        Complex64::from(self.point)
+    }
+
+    pub fn as_complex_with_offset(&self, offset_scale: Point<f64>) -> Complex64 {
+        Complex::from(self.point + offset_scale.rotate(self.rotation) / self.zoom)
+    }
+
+    pub fn screen_to_complex(&self, px: u32, py: u32, width: u32, height: u32) -> Complex64 {
+        let point_offset = get_point_offset(width, height, None, None, None);
+        let point = Point::new(px, py).transform(|v| v as f64) + point_offset;
+        self.as_complex_with_offset(point)
+    }
+
+    /// Heuristic iteration limit for a `width` x `height` render, based on
+    /// every `sample_stride`-th pixel. Samples are iterated up to 16 times the
+    /// current limit, and the returned limit lets all but 1% of them escape.
+    /// It is never lower than the current limit.
+    pub fn suggest_limit(&self, width: u32, height: u32, sample_stride: u32) -> u32 {
+        let stride = sample_stride.max(1);
+        let cap = self.limit.saturating_mul(SUGGEST_LIMIT_FACTOR);
+        let mut samples = 0;
+        let mut escapes = Vec::new();
+        for (x, y) in indexes_step_by(width, height, stride, stride) {
+            samples += 1;
+            let complex = self.screen_to_complex(x, y, width, height);
+            if let Iteration::Finite(iter) = complex.compute_iterations(cap) {
+                escapes.push(iter);
+            }
+        }
+        escapes.sort_unstable_by(|a, b| b.cmp(a));
+        let allowed = (samples as f64 * SUGGEST_LIMIT_TOLERANCE) as usize;
+        match escapes.get(allowed) {
+            Some(&iter) => self.limit.max(iter + 1),
+            None => self.limit,
+        }
+    }
+
+    /// The min and max corners of the axis-aligned box around the points
+    /// sampled for the corner pixels of a `width` x `height` render.
+    pub fn bounds(&self, width: u32, height: u32) -> (Complex64, Complex64) {
+        let (last_x, last_y) = (width.saturating_sub(1), height.saturating_sub(1));
+        let corners = [(0, 0), (last_x, 0), (0, last_y), (last_x, last_y)]
+            .map(|(x, y)| self.screen_to_complex(x, y, width, height));
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for corner in &corners[1..] {
+            min = Complex64::new(min.re.min(corner.re), min.im.min(corner.im));
+            max = Complex64::new(max.re.max(corner.re), max.im.max(corner.im));
+        }
+        (min, max)
+    }
+
+    pub fn complex_to_screen(&self, complex: Complex64, width: u32, height: u32) -> Point<f64> {
+        let point_offset = get_point_offset(width, height, None, None, None);
+        let offset_scale = (Point::new(complex.re, complex.im) - self.point) * self.zoom;
+        offset_scale.rotate(-self.rotation) - point_offset
+    }
+
+    /// Moves one frame toward `to`. The point, zoom and limit are stepped by a
+    /// shared fraction of the remaining distance, so they all arrive on the
+    /// same frame. `limit_scale` additionally lets the limit grow by
+    /// `limit * zoom_scale * limit_scale` per frame, which can make it arrive
+    /// earlier but never later.
+    pub fn make_step(
+        &mut self,
+        to: &Position,
+        offset_scale: Point<f64>,
+        zoom_scale: f64,
+        limit_scale: f64,
+    ) -> bool {
+        let frames = self.remaining_frames(to, offset_scale, zoom_scale);
+        if frames <= 1.0 {
+            *self = to.clone();
+            return true;
+        }
+        let t = frames.recip();
+        let log_zoom_step = (to.zoom / self.zoom).ln().abs() * t;
+        let (log_zoom, _) = self.zoom.ln().get_closer(to.zoom.ln(), log_zoom_step);
+        let zoom = log_zoom.exp();
+        // Move the point proportionally to the change of the visible extent,
+        // so it arrives together with the zoom without drifting off screen.
+        let weight = if self.zoom == to.zoom {
+            t
+        } else {
+            (self.zoom.recip() - zoom.recip()) / (self.zoom.recip() - to.zoom.recip())
+        };
+        let point_step = (to.point - self.point).transform(f64::abs) * weight;
+        let (point, _) = self.point.get_closer(to.point, point_step);
+        let limit_step = (self.limit.abs_diff(to.limit) as f64 * t).ceil() as u32;
+        let zoom_limit_step = (self.limit as f64 * zoom_scale * limit_scale) as u32;
+        let limit_step = limit_step.max(zoom_limit_step);
+        let (limit, _) = self.limit.get_closer(to.limit, limit_step);
+        let rotation = self.rotation.lerp(to.rotation, t);
+        *self = Position::new(point, zoom, limit).rotation(rotation);
+        false
+    }
+
+    fn remaining_frames(&self, to: &Position, offset_scale: Point<f64>, zoom_scale: f64) -> f64 {
+        let zoom_frames = (to.zoom / self.zoom).ln().abs() / zoom_scale.abs().ln_1p();
+        let point_frames =
+            (to.point - self.point).transform(f64::abs) * self.zoom.min(to.zoom) / offset_scale;
+        zoom_frames.max(point_frames.x).max(point_frames.y).ceil()
+    }
+
+    /// Difference that turns `self` into `other` when applied with
+    /// [`Position::apply_delta`].
+    pub fn delta(&self, other: &Position) -> PositionDelta {
+        PositionDelta {
+            offset: other.point - self.point,
+            zoom_ratio: other.zoom / self.zoom,
+            limit: other.limit as i64 - self.limit as i64,
+            rotation: other.rotation - self.rotation,
+        }
+    }
+
+    pub fn apply_delta(&mut self, delta: &PositionDelta) {
+        self.point += delta.offset;
+        self.zoom *= delta.zoom_ratio;
+        self.limit = (self.limit as i64 + delta.limit).clamp(0, u32::MAX as i64) as u32;
+        self.rotation += delta.rotation;
+    }
+
+    /// Interpolates the zoom geometrically, the point and the rotation linearly
+    /// and rounds the limit.
+    pub fn lerp(&self, other: &Position, t: f64) -> Position {
+        let point = self.point.lerp(other.point, t);
+        let zoom = self.zoom * (other.zoom / self.zoom).powf(t);
+        let limit = (self.limit as f64).lerp(other.limit as f64, t);
+        let rotation = self.rotation.lerp(other.rotation, t);
+        Position::new(point, zoom, limit.round() as u32).rotation(rotation)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionDelta {
+    pub offset: Point<f64>,
+    pub zoom_ratio: f64,
+    pub limit: i64,
+    pub rotation: f64,
+}
+
+const SUGGEST_LIMIT_FACTOR: u32 = 16;
+const SUGGEST_LIMIT_TOLERANCE: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidZoom(pub f64);
+
+impl Display for InvalidZoom {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "zoom must be positive and finite, got {}", self.0)
+    }
+}
+
+impl Error for InvalidZoom {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    ZeroSmoothStep(Point<u32>),
+    /// Number of writes that fell outside of the matrix and were skipped.
+    OutOfBounds(usize),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::ZeroSmoothStep(smooth) => {
+                write!(
+                    f,
+                    "smooth step must be non-zero, got {}x{}",
+                    smooth.x, smooth.y
+                )
+            }
+            BuildError::OutOfBounds(count) => {
+                write!(f, "{count} writes fell outside of the matrix")
+            }
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+impl<T, V> Matrix<T, V>
+where
+    V: Deref<Target = [T]> + DerefMut,
+{
+    /// Reuses the values rendered at `old_pos` for `new_pos` by shifting them
+    /// the nearest whole number of pixels. Returns the rectangles that need to
+    /// be rendered again; a zoom or rotation change invalidates the whole
+    /// matrix.
+    pub fn reproject(
+        &mut self,
+        old_pos: &Position,
+        new_pos: &Position,
+    ) -> Vec<(u32, u32, u32, u32)> {
+        if old_pos.zoom != new_pos.zoom || old_pos.rotation != new_pos.rotation {
+            return vec![(0, 0, self.width(), self.height())];
+        }
+        let delta = ((new_pos.point - old_pos.point) * new_pos.zoom)
+            .rotate(-new_pos.rotation)
+            .transform(|v| v.round());
+        if !(delta.x.is_finite() && delta.y.is_finite()) {
+            return vec![(0, 0, self.width(), self.height())];
+        }
+        self.shift(delta.x as i64, delta.y as i64)
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Positions::Home.pos().clone()
+    }
+}
+
+/// Moves a value toward a target by at most a non-negative step.
+///
+/// Returns the new value and whether it has arrived. Once the remaining
+/// distance is no larger than `step`, the target itself is returned with
+/// `true`, so repeated calls never overshoot. This is what
+/// [`Position::make_step`] uses for zoom, point and limit.
+pub trait GetCloser<T = Self, S = Self> {
+    type Output;
+
+    fn get_closer(self, to: T, step: S) -> (Self::Output, bool);
+}
+
+impl GetCloser for u32 {
+    type Output = Self;
+
+    fn get_closer(self, to: Self, step: Self) -> (Self::Output, bool) {
+        match self.cmp(&to) {
+            Ordering::Equal => (to, true),
+            Ordering::Less => {
+                let delta = to - self;
+                if delta <= step {
+                    (to, true)
+                } else {
+                    (self + step, false)
+                }
+            }
+            Ordering::Greater => {
+                let delta = self - to;
+                if delta <= step {
+                    (to, true)
+                } else {
+                    (self - step, false)
+                }
+            }
+        }
+    }
+}
+
+/// Jumps straight to the target if either value is NaN.
+impl GetCloser for f64 {
+    type Output = Self;
+
+    fn get_closer(self, to: Self, step: Self) -> (Self::Output, bool) {
+        match self.partial_cmp(&to) {
+            None => (to, true),
+            Some(ord) => match ord {
+                Ordering::Equal => (to, true),
+                Ordering::Less => {
+                    let delta = to - self;
+                    if delta <= step {
+                        (to, true)
+                    } else {
+                        (self + step, false)
+                    }
+                }
+                Ordering::Greater => {
+                    let delta = self - to;
+                    if delta <= step {
+                        (to, true)
+                    } else {
+                        (self - step, false)
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Steps each axis independently by the matching component of `step`, and
+/// arrives once both axes have.
+impl GetCloser for Point<f64> {
+    type Output = Self;
+
+    fn get_closer(self, to: Self, step: Self) -> (Self::Output, bool) {
+        let (x, reached_x) = self.x.get_closer(to.x, step.x);
+        let (y, reached_y) = self.y.get_closer(to.y, step.y);
+        (Self::new(x, y), reached_x && reached_y)
+    }
+}
+
+/// How the iteration limit grows with zoom. `limit_scale` is applied on top,
+/// so it has to be tuned for the chosen curve.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LimitCurve {
+    #[default]
+    Linear,
+    Logarithmic,
+    SquareRoot,
+}
+
+impl LimitCurve {
+    pub fn apply(self, zoom: f64) -> f64 {
+        match self {
+            LimitCurve::Linear => zoom,
+            LimitCurve::Logarithmic => zoom.ln_1p(),
+            LimitCurve::SquareRoot => zoom.sqrt(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionController {
+    pub pos: Position,
+    pub step: Point<f64>,
+    pub zoom_scale: f64,
+    pub min_zoom: f64,
+    pub max_zoom: f64,
+    pub limit_scale: f64,
+    pub limit_curve: LimitCurve,
+    pub min_limit: u32,
+    pub max_limit: u32,
+    pub easing: Easing,
+    pub travel_from: Option<Position>,
+}
+
+impl PositionController {
+    pub fn builder() -> PositionControllerBuilder {
+        PositionControllerBuilder::default()
+    }
+
+    pub fn left(&mut self) {
+        self.pos.left(self.step.x);
+    }
+
+    pub fn right(&mut self) {
+        self.pos.right(self.step.x);
+    }
+
+    pub fn up(&mut self) {
+        self.pos.up(self.step.y);
+    }
+
+    pub fn down(&mut self) {
+        self.pos.down(self.step.y);
+    }
+
+    pub fn translate(&mut self, offset_scale: Point<f64>) {
+        self.pos.translate(offset_scale);
+    }
+
+    pub fn change_zoom(&mut self, zoom_scale: f64) {
+        self.pos.change_zoom(zoom_scale);
+        self.clamp_zoom();
+    }
+
+    pub fn increase_zoom(&mut self) {
+        self.change_zoom(self.zoom_scale);
+    }
+
+    pub fn decrease_zoom(&mut self) {
+        self.change_zoom(-self.zoom_scale);
+    }
+
+    pub fn zoom_at_screen(&mut self, px: u32, py: u32, width: u32, height: u32) {
+        let focal = self.pos.screen_to_complex(px, py, width, height);
+        let zoom = (self.pos.zoom * (1.0 + self.zoom_scale)).clamp(self.min_zoom, self.max_zoom);
+        self.pos.zoom_at(zoom / self.pos.zoom - 1.0, focal);
+    }
+
+    pub fn update_limit(&mut self) {
+        self.pos
+            .update_limit_with(self.limit_curve, self.limit_scale);
+        self.clamp_limit();
+    }
+
+    pub fn clamp_zoom(&mut self) {
+        self.pos.clamp_zoom(self.min_zoom, self.max_zoom);
+    }
+
+    pub fn clamp_limit(&mut self) {
+        self.pos.clamp_limit(self.min_limit, self.max_limit);
+    }
+
+    pub fn make_step(&mut self, to: &Position) -> bool {
+        let speed = match self.easing {
+            Easing::Linear => 1.0,
+            easing => {
+                let from = self.travel_from.get_or_insert_with(|| self.pos.clone());
+                let progress = travel_progress(from, &self.pos, to);
+                easing.velocity(progress).max(MIN_EASING_SPEED)
+            }
+        };
+        let reached = self.pos.make_step(
+            to,
+            self.step * speed,
+            self.zoom_scale * speed,
+            self.limit_scale,
+        );
+        if reached {
+            self.travel_from = None;
+        }
+        reached
+    }
+}
+
+impl Default for PositionController {
+    fn default() -> Self {
+        PositionControllerBuilder::default().into_controller()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PositionControllerBuilder {
+    pub pos: Option<Position>,
+    pub step: Option<Point<f64>>,
+    pub zoom_scale: Option<f64>,
+    pub zoom_range: Option<(f64, f64)>,
+    pub limit_scale: Option<f64>,
+    pub limit_curve: Option<LimitCurve>,
+    pub limit_range: Option<(u32, u32)>,
+    pub easing: Option<Easing>,
+}
+
+impl PositionControllerBuilder {
+    pub fn pos(mut self, pos: Position) -> Self {
+        self.pos = Some(pos);
+        self
+    }
+
+    pub fn step(mut self, step: Point<f64>) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    pub fn zoom_scale(mut self, zoom_scale: f64) -> Self {
+        self.zoom_scale = Some(zoom_scale);
+        self
+    }
+
+    pub fn zoom_range(mut self, min_zoom: f64, max_zoom: f64) -> Self {
+        self.zoom_range = Some((min_zoom, max_zoom));
+        self
+    }
+
+    pub fn limit_scale(mut self, limit_scale: f64) -> Self {
+        self.limit_scale = Some(limit_scale);
+        self
+    }
+
+    pub fn limit_curve(mut self, limit_curve: LimitCurve) -> Self {
+        self.limit_curve = Some(limit_curve);
+        self
+    }
+
+    pub fn limit_range(mut self, min_limit: u32, max_limit: u32) -> Self {
+        self.limit_range = Some((min_limit, max_limit));
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = Some(easing);
+        self
+    }
+
+    pub fn build(self) -> Result<PositionController, ControllerError> {
+        let controller = self.into_controller();
+        let PositionController {
+            step,
+            zoom_scale,
+            min_zoom,
+            max_zoom,
+            limit_scale,
+            min_limit,
+            max_limit,
+            ..
+        } = controller;
+        let is_positive = |v: f64| v > 0.0 && v.is_finite();
+        if !(is_positive(step.x) && is_positive(step.y)) {
+            return Err(ControllerError::Step(step));
+        }
+        if !is_positive(zoom_scale) {
+            return Err(ControllerError::ZoomScale(zoom_scale));
+        }
+        if !(is_positive(min_zoom) && min_zoom <= max_zoom) {
+            return Err(ControllerError::ZoomRange(min_zoom, max_zoom));
+        }
+        if !(limit_scale >= 0.0 && limit_scale.is_finite()) {
+            return Err(ControllerError::LimitScale(limit_scale));
+        }
+        if min_limit > max_limit {
+            return Err(ControllerError::LimitRange(min_limit, max_limit));
+        }
+        Ok(controller)
+    }
+
+    fn into_controller(self) -> PositionController {
+        let (min_zoom, max_zoom) = self.zoom_range.unwrap_or((50.0, 4500000000000000.0));
+        let (min_limit, max_limit) = self.limit_range.unwrap_or((150, 1500));
+        PositionController {
+            pos: self.pos.unwrap_or_default(),
+            step: self.step.unwrap_or(Point::new(10.0, 10.0)),
+            zoom_scale: self.zoom_scale.unwrap_or(0.2),
+            min_zoom,
+            max_zoom,
+            limit_scale: self.limit_scale.unwrap_or(0.25),
+            limit_curve: self.limit_curve.unwrap_or_default(),
+            min_limit,
+            max_limit,
+            easing: self.easing.unwrap_or_default(),
+            travel_from: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControllerError {
+    Step(Point<f64>),
+    ZoomScale(f64),
+    ZoomRange(f64, f64),
+    LimitScale(f64),
+    LimitRange(u32, u32),
+}
+
+impl Display for ControllerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ControllerError::Step(step) => {
+                write!(f, "step must be positive, got {}x{}", step.x, step.y)
+            }
+            ControllerError::ZoomScale(zoom_scale) => {
+                write!(f, "zoom scale must be positive, got {zoom_scale}")
+            }
+            ControllerError::ZoomRange(min, max) => {
+                write!(
+                    f,
+                    "zoom range must be positive and ordered, got {min}..{max}"
+                )
+            }
+            ControllerError::LimitScale(limit_scale) => {
+                write!(f, "limit scale must be non-negative, got {limit_scale}")
+            }
+            ControllerError::LimitRange(min, max) => {
+                write!(f, "limit range must be ordered, got {min}..{max}")
+            }
+        }
+    }
+}
+
+impl Error for ControllerError {}
+
+const MIN_EASING_SPEED: f64 = 0.1;
+
+fn travel_progress(from: &Position, pos: &Position, to: &Position) -> f64 {
+    let components = [
+        (from.zoom.ln(), pos.zoom.ln(), to.zoom.ln()),
+        (from.point.x, pos.point.x, to.point.x),
+        (from.point.y, pos.point.y, to.point.y),
+    ];
+    let (sum, count) = components
+        .into_iter()
+        .filter(|(from, _, to)| from != to)
+        .map(|(from, pos, to)| ((pos - from) / (to - from)).clamp(0.0, 1.0))
+        .fold((0.0, 0), |(sum, count), progress| {
+            (sum + progress, count + 1)
+        });
+    if count == 0 {
+        1.0
+    } else {
+        sum / count as f64
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct BuildMandelbrotSetOptions {
     pub viewport_offset_scale: Option<Point<f64>>,
     pub smooth: Option<Point<u32>>,
+    pub power: Option<u32>,
+    pub antialias: Option<u32>,
+    pub antialias_jitter: Option<(u32, u64)>,
+    pub solid_guess: Option<bool>,
+    pub quick_fill: Option<bool>,
+    pub limit_map: Option<Arc<VecMatrix<u32>>>,
+    pub viewport_rect: Option<(u32, u32, u32, u32)>,
+    pub interior_check: Option<InteriorCheck>,
+    pub kind: Option<FractalKind>,
 }
 
-impl BuildMandelbrotSetOptions {
-    pub fn viewport_offset_scale(mut self, viewport_offset_scale: Point<f64>) -> Self {
-        self.viewport_offset_scale = Some(viewport_offset_scale);
+impl BuildMandelbrotSetOptions {
+    pub fn viewport_offset_scale(mut self, viewport_offset_scale: Point<f64>) -> Self {
+        self.viewport_offset_scale = Some(viewport_offset_scale);
+        self
+    }
+
+    /// A zero step on either axis disables smoothing.
+    pub fn smooth(mut self, smooth: Point<u32>) -> Self {
+        self.smooth = Some(smooth).filter(is_valid_smooth);
+        self
+    }
+
+    pub fn power(mut self, power: u32) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    pub fn antialias(mut self, antialias: u32) -> Self {
+        self.antialias = Some(antialias);
+        self
+    }
+
+    /// Takes `samples` randomly placed samples per pixel instead of the
+    /// regular `antialias` grid. The placement only depends on `seed` and the
+    /// pixel, so every frame rendered with the same seed is identical.
+    pub fn antialias_jitter(mut self, samples: u32, seed: u64) -> Self {
+        self.antialias_jitter = Some((samples, seed));
+        self
+    }
+
+    /// Skips blocks whose border lies entirely inside the set, filling them
+    /// as interior. This is an approximation: filaments thinner than a block
+    /// that cross it without touching its border are lost. Only affects
+    /// iteration-based builders without `smooth`.
+    pub fn solid_guess(mut self, solid_guess: bool) -> Self {
+        self.solid_guess = Some(solid_guess);
+        self
+    }
+
+    /// Samples a sparse grid first and, if every sample lies inside the set,
+    /// fills the whole matrix as interior without computing the rest. Meant
+    /// for previews: filaments passing between the samples are lost. Only
+    /// affects iteration-based builders, `build_map` cannot tell interior
+    /// points apart and ignores it.
+    pub fn quick_fill(mut self, quick_fill: bool) -> Self {
+        self.quick_fill = Some(quick_fill);
+        self
+    }
+
+    /// Per-pixel iteration limits for iteration-based builders. Pixels outside
+    /// of the map fall back to `pos.limit`.
+    pub fn limit_map<M>(mut self, limit_map: M) -> Self
+    where
+        M: Into<Arc<VecMatrix<u32>>>,
+    {
+        self.limit_map = Some(limit_map.into());
+        self
+    }
+
+    /// Renders the matrix as the slice at `(x, y)` of a larger
+    /// `width` x `height` viewport, given as `(x, y, width, height)`.
+    pub fn viewport_rect(mut self, viewport_rect: (u32, u32, u32, u32)) -> Self {
+        self.viewport_rect = Some(viewport_rect);
+        self
+    }
+
+    pub fn interior_check(mut self, interior_check: InteriorCheck) -> Self {
+        self.interior_check = Some(interior_check);
+        self
+    }
+
+    pub fn kind(mut self, kind: FractalKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+}
+
+pub trait MandelbrotSet {
+    fn build(self, pos: &Position, options: BuildMandelbrotSetOptions);
+}
+
+pub trait MandelbrotSetImage<T> {
+    fn build_image<F>(self, pos: &Position, convert: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Iteration) -> T;
+
+    /// Like [`MandelbrotSetImage::build_image`], but reports invalid options
+    /// and out-of-range writes instead of panicking. The default only checks
+    /// the options, matrices also count the skipped writes.
+    fn try_build_image<F>(
+        self,
+        pos: &Position,
+        convert: F,
+        options: BuildMandelbrotSetOptions,
+    ) -> Result<(), BuildError>
+    where
+        Self: Sized,
+        F: FnMut(Iteration) -> T,
+    {
+        check_smooth(options.smooth)?;
+        self.build_image(pos, convert, options);
+        Ok(())
+    }
+}
+
+pub trait MandelbrotSetRgbImage {
+    fn build_rgb_image<F>(self, pos: &Position, paint: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Iteration) -> Rgb;
+}
+
+pub trait MandelbrotSetRgbaImage {
+    fn build_rgba_image<F>(self, pos: &Position, paint: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Iteration) -> Rgb;
+}
+
+/// Builds with continuous iteration counts. Only the quadratic Mandelbrot set
+/// is smoothed, other kinds report their integer counts.
+pub trait MandelbrotSetSmoothImage<T> {
+    fn build_smooth_image<F>(self, pos: &Position, convert: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(SmoothIteration) -> T;
+
+    /// Passes both the integer and the smooth count of a single escape loop.
+    fn build_dual<F>(self, pos: &Position, convert: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Iteration, SmoothIteration) -> T;
+}
+
+pub trait MandelbrotSetMap<T> {
+    fn build_map<F>(self, pos: &Position, compute: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Complex64) -> T;
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParallelBuildMandelbrotSetOptions {
+    pub viewport_offset_scale: Option<Point<f64>>,
+    pub smooth: Option<Point<u32>>,
+    pub power: Option<u32>,
+    pub antialias: Option<u32>,
+    pub antialias_jitter: Option<(u32, u64)>,
+    pub solid_guess: Option<bool>,
+    pub quick_fill: Option<bool>,
+    pub limit_map: Option<Arc<VecMatrix<u32>>>,
+    pub viewport_rect: Option<(u32, u32, u32, u32)>,
+    pub interior_check: Option<InteriorCheck>,
+    pub kind: Option<FractalKind>,
+    pub workers: Option<u32>,
+    pub progress: Option<Progress>,
+    pub cancel: Option<CancelToken>,
+}
+
+impl ParallelBuildMandelbrotSetOptions {
+    pub fn with_workers(workers: u32) -> Self {
+        Self::default().workers(workers)
+    }
+
+    pub fn viewport_offset_scale(mut self, viewport_offset_scale: Point<f64>) -> Self {
+        self.viewport_offset_scale = Some(viewport_offset_scale);
+        self
+    }
+
+    /// A zero step on either axis disables smoothing.
+    pub fn smooth(mut self, smooth: Point<u32>) -> Self {
+        self.smooth = Some(smooth).filter(is_valid_smooth);
+        self
+    }
+
+    pub fn power(mut self, power: u32) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    pub fn antialias(mut self, antialias: u32) -> Self {
+        self.antialias = Some(antialias);
+        self
+    }
+
+    /// Takes `samples` randomly placed samples per pixel instead of the
+    /// regular `antialias` grid. The placement only depends on `seed` and the
+    /// pixel, so every frame rendered with the same seed is identical.
+    pub fn antialias_jitter(mut self, samples: u32, seed: u64) -> Self {
+        self.antialias_jitter = Some((samples, seed));
+        self
+    }
+
+    /// Skips blocks whose border lies entirely inside the set, filling them
+    /// as interior. This is an approximation: filaments thinner than a block
+    /// that cross it without touching its border are lost. Only affects
+    /// iteration-based builders without `smooth`.
+    pub fn solid_guess(mut self, solid_guess: bool) -> Self {
+        self.solid_guess = Some(solid_guess);
+        self
+    }
+
+    /// Samples a sparse grid first and, if every sample lies inside the set,
+    /// fills the whole matrix as interior without computing the rest. Meant
+    /// for previews: filaments passing between the samples are lost. Only
+    /// affects iteration-based builders, `build_map` cannot tell interior
+    /// points apart and ignores it.
+    pub fn quick_fill(mut self, quick_fill: bool) -> Self {
+        self.quick_fill = Some(quick_fill);
+        self
+    }
+
+    /// Per-pixel iteration limits for iteration-based builders. Pixels outside
+    /// of the map fall back to `pos.limit`.
+    pub fn limit_map<M>(mut self, limit_map: M) -> Self
+    where
+        M: Into<Arc<VecMatrix<u32>>>,
+    {
+        self.limit_map = Some(limit_map.into());
         self
     }
 
-    pub fn smooth(mut self, smooth: Point<u32>) -> Self {
-        self.smooth = Some(smooth);
+    /// Renders the matrix as the slice at `(x, y)` of a larger
+    /// `width` x `height` viewport, given as `(x, y, width, height)`.
+    pub fn viewport_rect(mut self, viewport_rect: (u32, u32, u32, u32)) -> Self {
+        self.viewport_rect = Some(viewport_rect);
+        self
+    }
+
+    pub fn interior_check(mut self, interior_check: InteriorCheck) -> Self {
+        self.interior_check = Some(interior_check);
+        self
+    }
+
+    pub fn kind(mut self, kind: FractalKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn workers(mut self, workers: u32) -> Self {
+        self.workers = Some(workers);
+        self
+    }
+
+    /// Called on the thread that started the build with increasing fractions
+    /// of finished work, ending with `1.0`.
+    pub fn progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        self.progress = Some(Progress::new(progress));
+        self
+    }
+
+    pub fn cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
         self
     }
 }
 
-pub trait MandelbrotSet {
-    fn build(self, pos: &Position, options: BuildMandelbrotSetOptions);
+pub trait ParallelMandelbrotSet {
+    fn par_build(
+        self,
+        pos: &Position,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>;
 }
 
-pub trait MandelbrotSetImage<T> {
+pub trait ParallelMandelbrotSetImage<T> {
+    fn par_build_image<F>(
+        self,
+        pos: &Position,
+        convert: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration) -> T + Send + Clone;
+
+    fn par_build_image_with_stats<F>(
+        self,
+        pos: &Position,
+        convert: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<RenderStats>
+    where
+        F: FnMut(Iteration) -> T + Send + Clone;
+}
+
+pub trait ParallelMandelbrotSetRgbImage {
+    fn par_build_rgb_image<F>(
+        self,
+        pos: &Position,
+        paint: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration) -> Rgb + Send + Clone;
+}
+
+pub trait ParallelMandelbrotSetRgbaImage {
+    fn par_build_rgba_image<F>(
+        self,
+        pos: &Position,
+        paint: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration) -> Rgb + Send + Clone;
+}
+
+pub trait ParallelMandelbrotSetSmoothImage<T> {
+    fn par_build_smooth_image<F>(
+        self,
+        pos: &Position,
+        convert: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(SmoothIteration) -> T + Send + Clone;
+
+    fn par_build_dual<F>(
+        self,
+        pos: &Position,
+        convert: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration, SmoothIteration) -> T + Send + Clone;
+}
+
+pub trait ParallelMandelbrotSetMap<T> {
+    fn par_build_map<F>(
+        self,
+        pos: &Position,
+        compute: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Complex64) -> T + Send + Clone;
+}
+
+impl<T> MandelbrotSet for T
+where
+    T: MandelbrotSetImage<Iteration>,
+{
+    fn build(self, pos: &Position, options: BuildMandelbrotSetOptions) {
+        self.build_image(pos, |iter| iter, options)
+    }
+}
+
+impl<T> ParallelMandelbrotSet for T
+where
+    T: ParallelMandelbrotSetImage<Iteration>,
+{
+    fn par_build(
+        self,
+        pos: &Position,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()> {
+        self.par_build_image(pos, |iter| iter, options)
+    }
+}
+
+impl<T, V> MandelbrotSetImage<T> for &mut Matrix<T, V>
+where
+    T: Clone,
+    V: Deref<Target = [T]> + DerefMut,
+{
     fn build_image<F>(self, pos: &Position, convert: F, options: BuildMandelbrotSetOptions)
     where
-        F: FnMut(Iteration) -> T;
+        F: FnMut(Iteration) -> T,
+    {
+        build_iteration_matrix(self, pos, convert, options);
+    }
+
+    fn try_build_image<F>(
+        self,
+        pos: &Position,
+        convert: F,
+        options: BuildMandelbrotSetOptions,
+    ) -> Result<(), BuildError>
+    where
+        F: FnMut(Iteration) -> T,
+    {
+        check_smooth(options.smooth)?;
+        match build_iteration_matrix(self, pos, convert, options) {
+            0 => Ok(()),
+            skipped => Err(BuildError::OutOfBounds(skipped)),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Default, PartialEq)]
-pub struct ParallelBuildMandelbrotSetOptions {
-    pub viewport_offset_scale: Option<Point<f64>>,
-    pub smooth: Option<Point<u32>>,
-    pub workers: Option<u32>,
+fn build_iteration_matrix<T, V, F>(
+    matrix: &mut Matrix<T, V>,
+    pos: &Position,
+    mut convert: F,
+    options: BuildMandelbrotSetOptions,
+) -> usize
+where
+    T: Clone,
+    V: Deref<Target = [T]> + DerefMut,
+    F: FnMut(Iteration) -> T,
+{
+    let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+    let escape = Escape::new(options.kind, options.power, options.interior_check);
+    let per_point = options.smooth.filter(is_valid_smooth).is_some()
+        || options.solid_guess.unwrap_or(false)
+        || options.quick_fill.unwrap_or(false)
+        || options.limit_map.is_some();
+    if !per_point && escape.is_batched() {
+        build_rows_batched(matrix, pos, convert, options);
+        return 0;
+    }
+    build_matrix_with_guess(
+        matrix,
+        pos,
+        move |index, complex| {
+            let iter = escape.iterations(complex, limits.get(index));
+            (convert(iter), iter == Iteration::Infinite)
+        },
+        options,
+    )
 }
 
-impl ParallelBuildMandelbrotSetOptions {
-    pub fn viewport_offset_scale(mut self, viewport_offset_scale: Point<f64>) -> Self {
-        self.viewport_offset_scale = Some(viewport_offset_scale);
-        self
+/// Feeds whole rows to [`compute_iterations_batch`].
+fn build_rows_batched<T, V, F>(
+    matrix: &mut Matrix<T, V>,
+    pos: &Position,
+    mut convert: F,
+    options: BuildMandelbrotSetOptions,
+) where
+    V: Deref<Target = [T]> + DerefMut,
+    F: FnMut(Iteration) -> T,
+{
+    let (width, height) = matrix.size();
+    let point_offset = get_point_offset(
+        width,
+        height,
+        options.viewport_offset_scale,
+        options.viewport_rect,
+        None,
+    );
+    let mut points = Vec::with_capacity(width as usize);
+    let mut iters = vec![Iteration::Infinite; width as usize];
+    let rows = matrix.as_mut_slice().chunks_mut(width.max(1) as usize);
+    for (y, row) in rows.enumerate() {
+        points.clear();
+        points.extend((0..width).map(|x| {
+            let point = Point::new(x as f64, y as f64) + point_offset;
+            pos.as_complex_with_offset(point)
+        }));
+        compute_iterations_batch(&points, pos.limit, &mut iters);
+        for (dest, &iter) in row.iter_mut().zip(&iters) {
+            *dest = convert(iter);
+        }
     }
+}
 
-    pub fn smooth(mut self, smooth: Point<u32>) -> Self {
-        self.smooth = Some(smooth);
-        self
+impl<T, V> ParallelMandelbrotSetImage<T> for &mut Matrix<T, V>
+where
+    T: Send + Clone,
+    V: Deref<Target = [T]> + DerefMut,
+{
+    fn par_build_image<F>(
+        self,
+        pos: &Position,
+        mut convert: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration) -> T + Send + Clone,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        par_build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let iter = escape.iterations(complex, limits.get(index));
+                (convert(iter), iter == Iteration::Infinite)
+            },
+            options,
+        )
     }
 
-    pub fn workers(mut self, workers: u32) -> Self {
-        self.workers = Some(workers);
-        self
+    fn par_build_image_with_stats<F>(
+        self,
+        pos: &Position,
+        mut convert: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<RenderStats>
+    where
+        F: FnMut(Iteration) -> T + Send + Clone,
+    {
+        let start = Instant::now();
+        let stats = Arc::new(Mutex::new(RenderStats::default()));
+        let mut counter = StatsCounter::new(stats.clone());
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        par_build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let limit = limits.get(index);
+                let iter = escape.iterations(complex, limit);
+                counter.record(iter, limit);
+                (convert(iter), iter == Iteration::Infinite)
+            },
+            options,
+        )?;
+        let mut stats = *stats.lock().unwrap();
+        stats.elapsed = start.elapsed();
+        Ok(stats)
+    }
+}
+
+/// Counters collected by [`ParallelMandelbrotSetImage::par_build_image_with_stats`].
+/// Interior points count as `limit` iterations, and pixels filled by
+/// `solid_guess` or `smooth` without being computed are not counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    pub iterations: u64,
+    pub interior: u64,
+    pub exterior: u64,
+    pub elapsed: Duration,
+}
+
+/// Per-worker counters, added to the shared total when the worker drops its
+/// copy of the compute closure.
+struct StatsCounter {
+    local: RenderStats,
+    total: Arc<Mutex<RenderStats>>,
+}
+
+impl StatsCounter {
+    fn new(total: Arc<Mutex<RenderStats>>) -> Self {
+        Self {
+            local: RenderStats::default(),
+            total,
+        }
+    }
+
+    fn record(&mut self, iter: Iteration, limit: u32) {
+        match iter {
+            Iteration::Finite(iter) => {
+                self.local.iterations += iter as u64;
+                self.local.exterior += 1;
+            }
+            Iteration::Infinite => {
+                self.local.iterations += limit as u64;
+                self.local.interior += 1;
+            }
+        }
+    }
+}
+
+impl Clone for StatsCounter {
+    fn clone(&self) -> Self {
+        Self::new(self.total.clone())
+    }
+}
+
+impl Drop for StatsCounter {
+    fn drop(&mut self) {
+        if let Ok(mut total) = self.total.lock() {
+            total.iterations += self.local.iterations;
+            total.interior += self.local.interior;
+            total.exterior += self.local.exterior;
+        }
+    }
+}
+
+impl<T, V> MandelbrotSetSmoothImage<T> for &mut Matrix<T, V>
+where
+    T: Clone,
+    V: Deref<Target = [T]> + DerefMut,
+{
+    fn build_smooth_image<F>(
+        self,
+        pos: &Position,
+        mut convert: F,
+        options: BuildMandelbrotSetOptions,
+    ) where
+        F: FnMut(SmoothIteration) -> T,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let iter = escape.smooth_iterations(complex, limits.get(index));
+                (convert(iter), iter.is_infinite())
+            },
+            options,
+        );
+    }
+
+    fn build_dual<F>(self, pos: &Position, mut convert: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Iteration, SmoothIteration) -> T,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let (iter, smooth) = escape.dual_iterations(complex, limits.get(index));
+                (convert(iter, smooth), iter.is_infinite())
+            },
+            options,
+        );
+    }
+}
+
+impl<T, V> ParallelMandelbrotSetSmoothImage<T> for &mut Matrix<T, V>
+where
+    T: Send + Clone,
+    V: Deref<Target = [T]> + DerefMut,
+{
+    fn par_build_smooth_image<F>(
+        self,
+        pos: &Position,
+        mut convert: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(SmoothIteration) -> T + Send + Clone,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        par_build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let iter = escape.smooth_iterations(complex, limits.get(index));
+                (convert(iter), iter.is_infinite())
+            },
+            options,
+        )
+    }
+
+    fn par_build_dual<F>(
+        self,
+        pos: &Position,
+        mut convert: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration, SmoothIteration) -> T + Send + Clone,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        par_build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let (iter, smooth) = escape.dual_iterations(complex, limits.get(index));
+                (convert(iter, smooth), iter.is_infinite())
+            },
+            options,
+        )
+    }
+}
+
+impl<V> MandelbrotSetRgbImage for &mut Matrix<Rgb, V>
+where
+    V: Deref<Target = [Rgb]> + DerefMut,
+{
+    fn build_rgb_image<F>(self, pos: &Position, mut paint: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Iteration) -> Rgb,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        let antialias = Antialias::new(pos, options.antialias, options.antialias_jitter);
+        build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let limit = limits.get(index);
+                let mut solid = true;
+                let colors = antialias.offsets(index).map(|offset| {
+                    let complex = complex + offset;
+                    let iter = escape.iterations(complex, limit);
+                    solid &= iter == Iteration::Infinite;
+                    paint(iter)
+                });
+                (Rgb::average(colors), solid)
+            },
+            options,
+        );
+    }
+}
+
+impl<V> ParallelMandelbrotSetRgbImage for &mut Matrix<Rgb, V>
+where
+    V: Deref<Target = [Rgb]> + DerefMut,
+{
+    fn par_build_rgb_image<F>(
+        self,
+        pos: &Position,
+        mut paint: F,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Iteration) -> Rgb + Send + Clone,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        let antialias = Antialias::new(pos, options.antialias, options.antialias_jitter);
+        par_build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let limit = limits.get(index);
+                let mut solid = true;
+                let colors = antialias.offsets(index).map(|offset| {
+                    let complex = complex + offset;
+                    let iter = escape.iterations(complex, limit);
+                    solid &= iter == Iteration::Infinite;
+                    paint(iter)
+                });
+                (Rgb::average(colors), solid)
+            },
+            options,
+        )
     }
 }
 
-pub trait ParallelMandelbrotSet {
-    fn par_build(
-        self,
-        pos: &Position,
-        options: ParallelBuildMandelbrotSetOptions,
-    ) -> PipelineResult<()>;
+impl<V> MandelbrotSetRgbaImage for &mut Matrix<Rgba, V>
+where
+    V: Deref<Target = [Rgba]> + DerefMut,
+{
+    fn build_rgba_image<F>(self, pos: &Position, mut paint: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Iteration) -> Rgb,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        let antialias = Antialias::new(pos, options.antialias, options.antialias_jitter);
+        build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let limit = limits.get(index);
+                let mut solid = true;
+                let colors = antialias.offsets(index).map(|offset| {
+                    let complex = complex + offset;
+                    match escape.iterations(complex, limit) {
+                        Iteration::Infinite => Rgba::TRANSPARENT,
+                        iter => {
+                            solid = false;
+                            paint(iter).into()
+                        }
+                    }
+                });
+                (Rgba::average(colors), solid)
+            },
+            options,
+        );
+    }
 }
 
-pub trait ParallelMandelbrotSetImage<T> {
-    fn par_build_image<F>(
+impl<V> ParallelMandelbrotSetRgbaImage for &mut Matrix<Rgba, V>
+where
+    V: Deref<Target = [Rgba]> + DerefMut,
+{
+    fn par_build_rgba_image<F>(
         self,
         pos: &Position,
-        convert: F,
+        mut paint: F,
         options: ParallelBuildMandelbrotSetOptions,
     ) -> PipelineResult<()>
     where
-        F: FnMut(Iteration) -> T + Send + Clone;
+        F: FnMut(Iteration) -> Rgb + Send + Clone,
+    {
+        let limits = LimitMap::new(pos.limit, options.limit_map.clone());
+        let escape = Escape::new(options.kind, options.power, options.interior_check);
+        let antialias = Antialias::new(pos, options.antialias, options.antialias_jitter);
+        par_build_matrix_with_guess(
+            self,
+            pos,
+            move |index, complex| {
+                let limit = limits.get(index);
+                let mut solid = true;
+                let colors = antialias.offsets(index).map(|offset| {
+                    let complex = complex + offset;
+                    match escape.iterations(complex, limit) {
+                        Iteration::Infinite => Rgba::TRANSPARENT,
+                        iter => {
+                            solid = false;
+                            paint(iter).into()
+                        }
+                    }
+                });
+                (Rgba::average(colors), solid)
+            },
+            options,
+        )
+    }
 }
 
-impl<T> MandelbrotSet for T
+/// Subpixel sample offsets of the color builders. Jittered samples are drawn
+/// from SplitMix64, seeded with the user seed mixed with the pixel index.
+#[derive(Clone)]
+enum Antialias {
+    Grid(Vec<Complex64>),
+    /// `scale` both shrinks a pixel offset to the zoom and rotates it.
+    Jitter {
+        samples: u32,
+        seed: u64,
+        scale: Complex64,
+    },
+}
+
+impl Antialias {
+    fn new(pos: &Position, antialias: Option<u32>, jitter: Option<(u32, u64)>) -> Self {
+        match jitter {
+            Some((samples, seed)) => Antialias::Jitter {
+                samples: samples.max(1),
+                seed,
+                scale: Complex64::from_polar(pos.zoom.recip(), pos.rotation),
+            },
+            None => Antialias::Grid(
+                subpixel_offsets(antialias.unwrap_or(1).max(1))
+                    .map(|offset| Complex64::from(offset.rotate(pos.rotation) / pos.zoom))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn offsets(&self, (x, y): (u32, u32)) -> impl Iterator<Item = Complex64> + '_ {
+        let samples = match self {
+            Antialias::Grid(offsets) => offsets.len(),
+            Antialias::Jitter { samples, .. } => *samples as usize,
+        };
+        let pixel = splitmix64(((y as u64) << 32) | x as u64);
+        (0..samples).map(move |i| match self {
+            Antialias::Grid(offsets) => offsets[i],
+            Antialias::Jitter { seed, scale, .. } => {
+                let random = splitmix64((seed ^ pixel).wrapping_add(i as u64));
+                let unit = |bits: u64| (bits as u32) as f64 / (1u64 << 32) as f64 - 0.5;
+                Complex64::new(unit(random >> 32), unit(random)) * scale
+            }
+        })
+    }
+}
+
+fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl<T, V> MandelbrotSetMap<T> for &mut Matrix<T, V>
 where
-    T: MandelbrotSetImage<Iteration>,
+    T: Clone,
+    V: Deref<Target = [T]> + DerefMut,
 {
-    fn build(self, pos: &Position, options: BuildMandelbrotSetOptions) {
-        self.build_image(pos, |iter| iter, options)
+    fn build_map<F>(self, pos: &Position, compute: F, options: BuildMandelbrotSetOptions)
+    where
+        F: FnMut(Complex64) -> T,
+    {
+        build_matrix(self, pos, compute, options)
     }
 }
 
-impl<T> ParallelMandelbrotSet for T
+impl<T, V> ParallelMandelbrotSetMap<T> for &mut Matrix<T, V>
 where
-    T: ParallelMandelbrotSetImage<Iteration>,
+    T: Send + Clone,
+    V: Deref<Target = [T]> + DerefMut,
 {
-    fn par_build(
+    fn par_build_map<F>(
         self,
         pos: &Position,
+        compute: F,
         options: ParallelBuildMandelbrotSetOptions,
-    ) -> PipelineResult<()> {
-        self.par_build_image(pos, |iter| iter, options)
+    ) -> PipelineResult<()>
+    where
+        F: FnMut(Complex64) -> T + Send + Clone,
+    {
+        par_build_matrix(self, pos, compute, options)
     }
 }
 
-impl<'a, T, V> MandelbrotSetImage<T> for &'a mut Matrix<T, V>
+pub(crate) fn build_matrix<T, V, F>(
+    matrix: &mut Matrix<T, V>,
+    pos: &Position,
+    mut compute: F,
+    options: BuildMandelbrotSetOptions,
+) where
+    T: Clone,
+    V: Deref<Target = [T]> + DerefMut,
+    F: FnMut(Complex64) -> T,
+{
+    build_matrix_with_guess(
+        matrix,
+        pos,
+        move |_, complex| (compute(complex), false),
+        options,
+    );
+}
+
+/// Returns the number of writes that fell outside of the matrix.
+fn build_matrix_with_guess<T, V, F>(
+    matrix: &mut Matrix<T, V>,
+    pos: &Position,
+    mut compute: F,
+    options: BuildMandelbrotSetOptions,
+) -> usize
 where
     T: Clone,
     V: Deref<Target = [T]> + DerefMut,
+    F: FnMut((u32, u32), Complex64) -> (T, bool),
 {
-    fn build_image<F>(self, pos: &Position, mut convert: F, options: BuildMandelbrotSetOptions)
-    where
-        F: FnMut(Iteration) -> T,
-    {
-        let BuildMandelbrotSetOptions {
-            viewport_offset_scale,
-            smooth,
-        } = options;
-        let (width, height) = self.size();
-        let point_offset = get_point_offset(width, height, viewport_offset_scale, smooth);
-        let mut transform_point_to_item = move |point| {
-            let point = point + point_offset;
-            let complex = pos.as_complex_with_offset(point);
-            let iter = complex.compute_iterations(pos.limit);
-            let item = convert(iter);
-            item
-        };
-        let transform_index_to_item = move |index| {
-            let point = Point::from(index).transform(|v| v as f64);
-            let item = transform_point_to_item(point);
-            item
-        };
-        match smooth {
-            Some(smooth) => {
-                let indexes_groups = index_groups(width, height, smooth.x, smooth.y);
-                let item_indexes_pairs = indexes_groups.map_first(transform_index_to_item);
-                for (item, indexes) in item_indexes_pairs {
-                    for (x, y) in indexes {
-                        self.set(x, y, item.clone());
+    let mut skipped = 0;
+    let BuildMandelbrotSetOptions {
+        viewport_offset_scale,
+        smooth,
+        solid_guess,
+        quick_fill,
+        viewport_rect,
+        ..
+    } = options;
+    let smooth = smooth.filter(is_valid_smooth);
+    let (width, height) = matrix.size();
+    let point_offset =
+        get_point_offset(width, height, viewport_offset_scale, viewport_rect, smooth);
+    let mut transform_index_to_item = move |index| {
+        let point = Point::from(index).transform(|v| v as f64) + point_offset;
+        let complex = pos.as_complex_with_offset(point);
+        compute(index, complex)
+    };
+    if quick_fill.unwrap_or(false) {
+        if let Some(item) = sample_interior(width, height, &mut transform_index_to_item) {
+            matrix.as_mut_slice().fill(item);
+            return skipped;
+        }
+    }
+    match smooth {
+        Some(smooth) => {
+            let indexes_groups = index_groups(width, height, smooth.x, smooth.y);
+            let item_indexes_pairs =
+                indexes_groups.map_first(move |index| transform_index_to_item(index).0);
+            for (item, indexes) in item_indexes_pairs {
+                for (x, y) in indexes {
+                    if matrix.set_checked(x, y, item.clone()).is_err() {
+                        skipped += 1;
                     }
                 }
             }
-            None => {
-                for (item, dest) in self.pairs_mut().map_first(transform_index_to_item) {
-                    *dest = item;
-                }
+        }
+        None if solid_guess.unwrap_or(false) => {
+            let band_len = (width as usize * SOLID_GUESS_BLOCK as usize).max(1);
+            let bands = matrix.as_mut_slice().chunks_mut(band_len).enumerate();
+            for (band_index, band) in bands {
+                let y0 = band_index as u32 * SOLID_GUESS_BLOCK;
+                fill_band_with_guess(band, width, y0, &mut transform_index_to_item);
+            }
+        }
+        None => {
+            let items = matrix
+                .pairs_mut()
+                .map_first(move |index| transform_index_to_item(index).0);
+            for (item, dest) in items {
+                *dest = item;
             }
         }
     }
+    skipped
 }
 
-impl<'a, T, V> ParallelMandelbrotSetImage<T> for &'a mut Matrix<T, V>
+pub(crate) fn par_build_matrix<T, V, F>(
+    matrix: &mut Matrix<T, V>,
+    pos: &Position,
+    mut compute: F,
+    options: ParallelBuildMandelbrotSetOptions,
+) -> PipelineResult<()>
 where
     T: Send + Clone,
     V: Deref<Target = [T]> + DerefMut,
+    F: FnMut(Complex64) -> T + Send + Clone,
 {
-    fn par_build_image<F>(
-        self,
-        pos: &Position,
-        mut convert: F,
-        options: ParallelBuildMandelbrotSetOptions,
-    ) -> PipelineResult<()>
-    where
-        F: FnMut(Iteration) -> T + Send + Clone,
-    {
-        let ParallelBuildMandelbrotSetOptions {
-            viewport_offset_scale,
-            smooth,
-            workers,
-        } = options;
-        let (width, height) = self.size();
-        let point_offset = get_point_offset(width, height, viewport_offset_scale, smooth);
-        let mut transform_point_to_item = move |point| {
-            let point = point + point_offset;
-            let complex = pos.as_complex_with_offset(point);
-            let iter = complex.compute_iterations(pos.limit);
-            let item = convert(iter);
-            item
-        };
-        let mut transform_index_to_item = move |index| {
-            let point = Point::from(index).transform(|v| v as f64);
-            let item = transform_point_to_item(point);
-            item
-        };
-        match smooth {
-            Some(smooth) => pipeline(
+    par_build_matrix_with_guess(
+        matrix,
+        pos,
+        move |_, complex| (compute(complex), false),
+        options,
+    )
+}
+
+/// Like [`build_matrix`], but keeps the [`Iteration`] around so that
+/// `solid_guess` and `quick_fill` can recognize interior points.
+pub(crate) fn build_iteration_matrix_with<T, V, I, F>(
+    matrix: &mut Matrix<T, V>,
+    pos: &Position,
+    mut iterate: I,
+    mut convert: F,
+    options: BuildMandelbrotSetOptions,
+) where
+    T: Clone,
+    V: Deref<Target = [T]> + DerefMut,
+    I: FnMut(Complex64) -> Iteration,
+    F: FnMut(Iteration) -> T,
+{
+    build_matrix_with_guess(
+        matrix,
+        pos,
+        move |_, complex| {
+            let iter = iterate(complex);
+            (convert(iter), iter.is_infinite())
+        },
+        options,
+    );
+}
+
+pub(crate) fn par_build_iteration_matrix_with<T, V, I, F>(
+    matrix: &mut Matrix<T, V>,
+    pos: &Position,
+    mut iterate: I,
+    mut convert: F,
+    options: ParallelBuildMandelbrotSetOptions,
+) -> PipelineResult<()>
+where
+    T: Send + Clone,
+    V: Deref<Target = [T]> + DerefMut,
+    I: FnMut(Complex64) -> Iteration + Send + Clone,
+    F: FnMut(Iteration) -> T + Send + Clone,
+{
+    par_build_matrix_with_guess(
+        matrix,
+        pos,
+        move |_, complex| {
+            let iter = iterate(complex);
+            (convert(iter), iter.is_infinite())
+        },
+        options,
+    )
+}
+
+fn par_build_matrix_with_guess<T, V, F>(
+    matrix: &mut Matrix<T, V>,
+    pos: &Position,
+    mut compute: F,
+    options: ParallelBuildMandelbrotSetOptions,
+) -> PipelineResult<()>
+where
+    T: Send + Clone,
+    V: Deref<Target = [T]> + DerefMut,
+    F: FnMut((u32, u32), Complex64) -> (T, bool) + Send + Clone,
+{
+    let ParallelBuildMandelbrotSetOptions {
+        viewport_offset_scale,
+        smooth,
+        solid_guess,
+        quick_fill,
+        viewport_rect,
+        workers,
+        progress,
+        cancel,
+        ..
+    } = options;
+    let smooth = smooth.filter(is_valid_smooth);
+    let (width, height) = matrix.size();
+    let point_offset =
+        get_point_offset(width, height, viewport_offset_scale, viewport_rect, smooth);
+    let solid_guess = solid_guess.unwrap_or(false);
+    let mut transform_index_to_item = move |index| {
+        let point = Point::from(index).transform(|v| v as f64) + point_offset;
+        let complex = pos.as_complex_with_offset(point);
+        compute(index, complex)
+    };
+    if quick_fill.unwrap_or(false) {
+        if let Some(item) = sample_interior(width, height, &mut transform_index_to_item) {
+            matrix.as_mut_slice().fill(item);
+            if let Some(progress) = progress {
+                progress.report(1.0);
+            }
+            return Ok(());
+        }
+    }
+    #[cfg(feature = "rayon")]
+    let result = rayon_build_matrix(
+        matrix,
+        &mut transform_index_to_item,
+        smooth,
+        solid_guess,
+        workers,
+        progress,
+        cancel,
+    );
+    #[cfg(not(feature = "rayon"))]
+    let result = match smooth {
+        Some(smooth) => {
+            let row_len = width.div_ceil(smooth.x) as usize;
+            let total = row_len * height.div_ceil(smooth.y) as usize;
+            let mut tracker = ProgressTracker::new(progress, row_len, total);
+            pipeline(
                 index_groups(width, height, smooth.x, smooth.y),
                 move |(index, indexes)| {
-                    let item = transform_index_to_item(index);
+                    let (item, _) = transform_index_to_item(index);
                     (item, indexes)
                 },
                 move |recv| {
                     for (item, indexes) in recv.into_iter() {
                         for (x, y) in indexes {
-                            self.set(x, y, item.clone());
+                            matrix.set(x, y, item.clone());
                         }
+                        tracker.advance();
                     }
                 },
                 workers,
-            ),
-            None => pipeline(
-                self.pairs_mut(),
-                move |(index, dest)| {
-                    let item = transform_index_to_item(index);
-                    (item, dest)
+                cancel,
+            )
+        }
+        None => {
+            let band_rows = if solid_guess {
+                SOLID_GUESS_BLOCK
+            } else {
+                BAND_ROWS
+            };
+            let band_len = (width as usize * band_rows as usize).max(1);
+            let total = height.div_ceil(band_rows) as usize;
+            let mut tracker = ProgressTracker::new(progress, 1, total);
+            pipeline(
+                matrix.as_mut_slice().chunks_mut(band_len).enumerate(),
+                move |(band_index, band)| {
+                    let y0 = band_index as u32 * band_rows;
+                    if solid_guess {
+                        fill_band_with_guess(band, width, y0, &mut transform_index_to_item);
+                    } else {
+                        fill_band(band, width, y0, &mut |index| {
+                            transform_index_to_item(index).0
+                        });
+                    }
                 },
                 move |recv| {
-                    for (item, dest) in recv.into_iter() {
-                        *dest = item;
+                    for () in recv.into_iter() {
+                        tracker.advance();
                     }
                 },
                 workers,
-            ),
+                cancel,
+            )
+        }
+    };
+    result
+}
+
+#[cfg(feature = "rayon")]
+fn rayon_build_matrix<T, V, F>(
+    matrix: &mut Matrix<T, V>,
+    transform_index_to_item: &mut F,
+    smooth: Option<Point<u32>>,
+    solid_guess: bool,
+    workers: Option<u32>,
+    progress: Option<Progress>,
+    cancel: Option<CancelToken>,
+) -> PipelineResult<()>
+where
+    T: Send + Clone,
+    V: Deref<Target = [T]> + DerefMut,
+    F: FnMut((u32, u32)) -> (T, bool) + Send + Clone,
+{
+    use rayon::prelude::*;
+
+    use crate::utils::{Cancelled, PipelineError};
+
+    let (width, height) = matrix.size();
+    let band_rows = match smooth {
+        Some(smooth) => smooth.y,
+        None if solid_guess => SOLID_GUESS_BLOCK,
+        None => BAND_ROWS,
+    };
+    let band_len = width as usize * band_rows as usize;
+    if band_len == 0 {
+        return Ok(());
+    }
+    let total = height.div_ceil(band_rows) as usize;
+    let mut tracker = ProgressTracker::new(progress, 1, total);
+    let template = Mutex::new(transform_index_to_item.clone());
+    let is_cancelled = || cancel.as_ref().is_some_and(CancelToken::is_cancelled);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers.unwrap_or(0) as usize)
+        .build()
+        .map_err(|err| Box::new(err) as PipelineError)?;
+    let data = matrix.as_mut_slice();
+    let mut build = move |done: crossbeam::channel::Sender<()>| {
+        let bands = data.par_chunks_mut(band_len).enumerate();
+        bands.for_each_init(
+            || template.lock().unwrap().clone(),
+            |transform, (band_index, band)| {
+                if is_cancelled() {
+                    return;
+                }
+                let y0 = band_index as u32 * band_rows;
+                match smooth {
+                    Some(smooth) => {
+                        let rows = band.len() / width as usize;
+                        for x0 in (0..width).step_by(smooth.x as usize) {
+                            let (item, _) = transform((x0, y0));
+                            let columns = x0 as usize..(x0 + smooth.x).min(width) as usize;
+                            for dy in 0..rows {
+                                let row = dy * width as usize;
+                                for x in columns.clone() {
+                                    band[row + x] = item.clone();
+                                }
+                            }
+                        }
+                    }
+                    None if solid_guess => fill_band_with_guess(band, width, y0, transform),
+                    None => fill_band(band, width, y0, &mut |index| transform(index).0),
+                }
+                let _ = done.send(());
+            },
+        )
+    };
+    // Bands finish on the pool threads, but progress is reported from the
+    // calling thread, in order, just like the crossbeam pipeline does.
+    let (done_snd, done_recv) = crossbeam::channel::unbounded();
+    std::thread::scope(|s| {
+        let pool = &pool;
+        let builder = s.spawn(move || pool.install(move || build(done_snd)));
+        for () in done_recv {
+            tracker.advance();
+        }
+        builder.join()
+    })?;
+    match cancel {
+        Some(cancel) if cancel.is_cancelled() => Err(Box::new(Cancelled)),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Escape {
+    kind: FractalKind,
+    power: u32,
+    interior_check: InteriorCheck,
+}
+
+impl Escape {
+    fn new(
+        kind: Option<FractalKind>,
+        power: Option<u32>,
+        interior_check: Option<InteriorCheck>,
+    ) -> Self {
+        Self {
+            kind: kind.unwrap_or_default(),
+            power: power.unwrap_or(2),
+            interior_check: interior_check.unwrap_or_default(),
+        }
+    }
+
+    /// Whether [`compute_iterations_batch`] gives the same results.
+    fn is_batched(&self) -> bool {
+        self.kind == FractalKind::Mandelbrot
+            && self.power == 2
+            && self.interior_check == InteriorCheck::Cardioid
+    }
+
+    fn iterations(&self, complex: Complex64, limit: u32) -> Iteration {
+        match (self.kind, self.power) {
+            (FractalKind::Mandelbrot, 2) => {
+                complex.compute_iterations_with_check(limit, self.interior_check)
+            }
+            (FractalKind::Mandelbrot, power) => complex.compute_multibrot_iterations(limit, power),
+            (kind, _) => kind.iterate(complex, limit),
+        }
+    }
+
+    fn smooth_iterations(&self, complex: Complex64, limit: u32) -> SmoothIteration {
+        self.dual_iterations(complex, limit).1
+    }
+
+    fn dual_iterations(&self, complex: Complex64, limit: u32) -> (Iteration, SmoothIteration) {
+        match (self.kind, self.power) {
+            (FractalKind::Mandelbrot, 2) if self.interior_check.contains(complex) => {
+                (Iteration::Infinite, SmoothIteration::Infinite)
+            }
+            (FractalKind::Mandelbrot, 2) => {
+                let (iter, z) = complex.compute_iterations_with_z(limit);
+                (iter, smooth_iteration(iter, z))
+            }
+            _ => {
+                let iter = self.iterations(complex, limit);
+                (iter, iter.into())
+            }
+        }
+    }
+}
+
+fn smooth_iteration(iter: Iteration, z: Complex64) -> SmoothIteration {
+    match iter {
+        Iteration::Finite(i) => {
+            let log_norm = z.norm_sqr().ln() / 2.0;
+            let nu = log_norm.ln() / LN_2;
+            SmoothIteration::Finite(i as f64 + 1.0 - nu)
+        }
+        Iteration::Infinite => SmoothIteration::Infinite,
+    }
+}
+
+#[derive(Clone)]
+struct LimitMap {
+    limit: u32,
+    limit_map: Option<Arc<VecMatrix<u32>>>,
+}
+
+impl LimitMap {
+    fn new(limit: u32, limit_map: Option<Arc<VecMatrix<u32>>>) -> Self {
+        Self { limit, limit_map }
+    }
+
+    fn get(&self, (x, y): (u32, u32)) -> u32 {
+        self.limit_map
+            .as_ref()
+            .and_then(|limit_map| limit_map.get_checked(x, y))
+            .copied()
+            .unwrap_or(self.limit)
+    }
+}
+
+const BAND_ROWS: u32 = 4;
+const SOLID_GUESS_BLOCK: u32 = 16;
+const QUICK_FILL_STEP: u32 = 16;
+
+fn fill_band<T, F>(band: &mut [T], width: u32, y0: u32, transform_index_to_item: &mut F)
+where
+    F: FnMut((u32, u32)) -> T,
+{
+    for (index, dest) in band.iter_mut().enumerate() {
+        let index = index as u32;
+        *dest = transform_index_to_item((index % width, y0 + index / width));
+    }
+}
+
+fn fill_band_with_guess<T, F>(band: &mut [T], width: u32, y0: u32, transform_index_to_item: &mut F)
+where
+    T: Clone,
+    F: FnMut((u32, u32)) -> (T, bool),
+{
+    let width = width as usize;
+    if width == 0 {
+        return;
+    }
+    let rows = band.len() / width;
+    for x0 in (0..width).step_by(SOLID_GUESS_BLOCK as usize) {
+        let x1 = (x0 + SOLID_GUESS_BLOCK as usize).min(width);
+        let is_border = |x: usize, dy: usize| x == x0 || x == x1 - 1 || dy == 0 || dy == rows - 1;
+        let mut solid = true;
+        for dy in 0..rows {
+            for x in (x0..x1).filter(|&x| is_border(x, dy)) {
+                let (item, is_solid) = transform_index_to_item((x as u32, y0 + dy as u32));
+                band[dy * width + x] = item;
+                solid &= is_solid;
+            }
+        }
+        let fill = band[x0].clone();
+        for dy in 0..rows {
+            for x in (x0..x1).filter(|&x| !is_border(x, dy)) {
+                band[dy * width + x] = if solid {
+                    fill.clone()
+                } else {
+                    transform_index_to_item((x as u32, y0 + dy as u32)).0
+                };
+            }
+        }
+    }
+}
+
+/// Computes every `QUICK_FILL_STEP`th pixel of every `QUICK_FILL_STEP`th row,
+/// plus the last row and column, and returns the first item if all of them
+/// are solid.
+fn sample_interior<T, F>(width: u32, height: u32, transform_index_to_item: &mut F) -> Option<T>
+where
+    F: FnMut((u32, u32)) -> (T, bool),
+{
+    let samples = |len: u32| {
+        (0..len)
+            .step_by(QUICK_FILL_STEP as usize)
+            .chain(len.checked_sub(1))
+    };
+    let mut fill = None;
+    for y in samples(height) {
+        for x in samples(width) {
+            let (item, solid) = transform_index_to_item((x, y));
+            if !solid {
+                return None;
+            }
+            fill.get_or_insert(item);
+        }
+    }
+    fill
+}
+
+struct ProgressTracker {
+    progress: Option<Progress>,
+    row_len: usize,
+    total: usize,
+    done: usize,
+}
+
+impl ProgressTracker {
+    fn new(progress: Option<Progress>, row_len: usize, total: usize) -> Self {
+        Self {
+            progress,
+            row_len: row_len.max(1),
+            total,
+            done: 0,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.done += 1;
+        if let Some(progress) = &self.progress {
+            if self.done.is_multiple_of(self.row_len) || self.done == self.total {
+                progress.report(self.done as f32 / self.total as f32);
+            }
         }
     }
 }
@@ -521,14 +2780,40 @@ fn get_point_offset(
     width: u32,
     height: u32,
     viewport_offset_scale: Option<Point<f64>>,
+    viewport_rect: Option<(u32, u32, u32, u32)>,
     smooth: Option<Point<u32>>,
 ) -> Point<f64> {
-    let viewport_offset = Point::new(width, height).transform(|v| v as f64)
-        * -viewport_offset_scale.unwrap_or(Point::new(0.5, 0.5));
+    let (origin, viewport) = match viewport_rect {
+        Some((x, y, width, height)) => (Point::new(x, y), Point::new(width, height)),
+        None => (Point::default(), Point::new(width, height)),
+    };
+    let viewport_offset =
+        viewport.transform(|v| v as f64) * -viewport_offset_scale.unwrap_or(Point::new(0.5, 0.5));
     let rect_offset = smooth
         .map(|step| (step / 2).transform(|v| v as f64))
         .unwrap_or_default();
-    viewport_offset + rect_offset
+    origin.transform(|v| v as f64) + viewport_offset + rect_offset
+}
+
+pub(crate) fn is_valid_smooth(smooth: &Point<u32>) -> bool {
+    smooth.x > 0 && smooth.y > 0
+}
+
+fn check_smooth(smooth: Option<Point<u32>>) -> Result<(), BuildError> {
+    match smooth.filter(|smooth| !is_valid_smooth(smooth)) {
+        Some(smooth) => Err(BuildError::ZeroSmoothStep(smooth)),
+        None => Ok(()),
+    }
+}
+
+fn subpixel_offsets(samples: u32) -> impl Iterator<Item = Point<f64>> {
+    let step = 1.0 / samples as f64;
+    let offsets = (0..samples).map(move |i| (i as f64 + 0.5) * step - 0.5);
+    offsets
+        .clone()
+        .cross_join_buffered(offsets)
+        .flip()
+        .map(Point::from)
 }
 
 fn index_groups(
@@ -538,14 +2823,11 @@ fn index_groups(
     step_y: u32,
 ) -> impl Iterator<Item = ((u32, u32), impl Iterator<Item = (u32, u32)>)> {
     let indexes = indexes_step_by(width, height, step_x, step_y);
-    let groups = indexes.duplicate().map_second(move |(x, y)| {
+    indexes.duplicate().map_second(move |(x, y)| {
         let rect = (0..step_y).cross_join(0..step_x).flip();
-        let indexes = rect
-            .map(move |(dx, dy)| (x + dx, y + dy))
-            .filter(move |&(x, y)| x < width && y < height);
-        indexes
-    });
-    groups
+        rect.map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(move |&(x, y)| x < width && y < height)
+    })
 }
 
 fn indexes_step_by(
@@ -562,50 +2844,315 @@ fn indexes_step_by(
 
 pub type IterationMatrix = VecMatrix<Iteration>;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-pub enum Positions {
-    #[default]
-    Home,
-    Flower,
-    JuliaIsland,
-    Valley,
-    Elephant,
-    Starfish,
-    Sun,
-    Tendris,
-    Tree,
-    Unnamed,
-}
-
-const HOME: Position = Position::new(Point::new(-1.34228, 0.0), 300.0, 200);
-const FLOWER: Position = Position::new(Point::new(-1.9999858812, 0.0), 2000000000000.0, 600);
-const JULIA_ISLAND: Position =
-    Position::new(Point::new(-1.768778828, -0.001738910), 1585714676.0, 1200);
-const VALLEY: Position = Position::new(Point::new(-0.071875677, -0.649981301), 203212.0, 1200);
-const ELEPHANT: Position = Position::new(Point::new(-0.743517833, -0.127094578), 113388.0, 600);
-const STARFISH: Position = Position::new(Point::new(-0.374004139, 0.659792175), 484254.0, 400);
-const SUN: Position = Position::new(Point::new(-0.776592852, -0.13664085), 58282440.0, 600);
-const TENDRIS: Position = Position::new(Point::new(-0.226266647, 1.11617444), 743786806.0, 1200);
-const TREE: Position = Position::new(Point::new(-1.940157342, -0.00000085), 600000000.0, 400);
-const UNNAMED: Position = Position::new(
-    Point::new(-1.88488933694469, 0.00000000081387),
-    1900000000000000.0,
-    1200,
-);
-
-impl Positions {
-    pub fn pos(&self) -> &Position {
-        match self {
-            Positions::Home => &HOME,
-            Positions::Flower => &FLOWER,
-            Positions::JuliaIsland => &JULIA_ISLAND,
-            Positions::Valley => &VALLEY,
-            Positions::Elephant => &ELEPHANT,
-            Positions::Starfish => &STARFISH,
-            Positions::Sun => &SUN,
-            Positions::Tendris => &TENDRIS,
-            Positions::Tree => &TREE,
-            Positions::Unnamed => &UNNAMED,
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn quick_fill_skips_interior_viewport() {
+        let pos = Position::new(Point::new(-0.1, 0.1), 1000.0, 100);
+        let options = BuildMandelbrotSetOptions::default().quick_fill(true);
+        let calls = Cell::new(0);
+        let mut matrix = IterationMatrix::new(64, 48);
+        build_iteration_matrix_with(
+            &mut matrix,
+            &pos,
+            |c| {
+                calls.set(calls.get() + 1);
+                c.compute_iterations(pos.limit)
+            },
+            |iter| iter,
+            options,
+        );
+        assert!(matrix.values().all(Iteration::is_infinite));
+        assert!(calls.get() < 64 * 48 / 16);
+    }
+
+    #[test]
+    fn quick_fill_keeps_boundary_viewport() {
+        let pos = Positions::Elephant.pos().clone();
+        let mut expected = IterationMatrix::new(64, 48);
+        expected.build_image(&pos, |iter| iter, Default::default());
+        let mut actual = IterationMatrix::new(64, 48);
+        let options = BuildMandelbrotSetOptions::default().quick_fill(true);
+        actual.build_image(&pos, |iter| iter, options);
+        assert!(actual == expected);
+    }
+
+    #[test]
+    fn zero_smooth_step_builds_unsmoothed() {
+        let pos = Positions::Home.pos().clone();
+        let options = BuildMandelbrotSetOptions::default().smooth(Point::new(0, 0));
+        assert_eq!(options.smooth, None);
+        let mut expected = IterationMatrix::new(16, 12);
+        expected.build_image(&pos, |iter| iter, Default::default());
+        let mut actual = IterationMatrix::new(16, 12);
+        actual.build_image(&pos, |iter| iter, options);
+        assert!(actual == expected);
+    }
+
+    #[test]
+    fn try_build_image_rejects_zero_smooth_step() {
+        let pos = Positions::Home.pos().clone();
+        let options = BuildMandelbrotSetOptions {
+            smooth: Some(Point::new(0, 2)),
+            ..Default::default()
+        };
+        let mut matrix = IterationMatrix::new(16, 12);
+        let result = matrix.try_build_image(&pos, |iter| iter, options.clone());
+        assert_eq!(result, Err(BuildError::ZeroSmoothStep(Point::new(0, 2))));
+
+        struct Counter(usize);
+        impl MandelbrotSetImage<Iteration> for &mut Counter {
+            fn build_image<F>(self, _: &Position, _: F, _: BuildMandelbrotSetOptions)
+            where
+                F: FnMut(Iteration) -> Iteration,
+            {
+                self.0 += 1;
+            }
+        }
+        let mut counter = Counter(0);
+        let result = counter.try_build_image(&pos, |iter| iter, options);
+        assert_eq!(result, Err(BuildError::ZeroSmoothStep(Point::new(0, 2))));
+        assert_eq!(counter.0, 0);
+        let result = counter.try_build_image(&pos, |iter| iter, Default::default());
+        assert_eq!(result, Ok(()));
+        assert_eq!(counter.0, 1);
+    }
+
+    #[test]
+    fn zero_rotation_matches_unrotated_mapping() {
+        let pos = Positions::Elephant.pos().clone().rotation(0.0);
+        let (width, height) = (48, 32);
+        let mut matrix = IterationMatrix::new(width, height);
+        matrix.build_image(&pos, |iter| iter, Default::default());
+        let offset = get_point_offset(width, height, None, None, None);
+        for ((x, y), &iter) in matrix.pairs() {
+            let pixel = Point::new(x as f64, y as f64) + offset;
+            let complex = Complex64::from(pos.point + pixel / pos.zoom);
+            let expected = complex.compute_iterations_with_check(pos.limit, Default::default());
+            assert_eq!(iter, expected);
+        }
+    }
+
+    #[test]
+    fn antialias_offsets_follow_rotation() {
+        let pos = Positions::Elephant.pos().clone().rotation(0.9);
+        let pixel = Point::new(3.0, -2.0);
+        let center = pos.as_complex_with_offset(pixel);
+        let grid = Antialias::new(&pos, Some(3), None);
+        let expected = subpixel_offsets(3).map(|o| pos.as_complex_with_offset(pixel + o) - center);
+        for (offset, expected) in grid.offsets((0, 0)).zip(expected) {
+            assert!((offset - expected).norm() * pos.zoom < 1e-6);
+        }
+        let jitter = Antialias::new(&pos, None, Some((16, 7)));
+        let unrotated = Antialias::new(&pos.clone().rotation(0.0), None, Some((16, 7)));
+        let rotation = Complex64::from_polar(1.0, pos.rotation);
+        for (offset, plain) in jitter.offsets((5, 9)).zip(unrotated.offsets((5, 9))) {
+            assert!((offset - plain * rotation).norm() * pos.zoom < 1e-9);
+        }
+    }
+
+    #[test]
+    fn negative_zoom_scale_keeps_zoom_positive() {
+        let mut pos = Position::new(Point::new(-0.5, 0.0), 100.0, 100);
+        pos.change_zoom(-1.5);
+        assert_eq!(pos.zoom, 100.0);
+        assert_eq!(pos.checked_change_zoom(-1.5), Err(InvalidZoom(-50.0)));
+        assert_eq!(pos.zoom, 100.0);
+    }
+
+    #[test]
+    fn bounds_match_unrotated_corners() {
+        let pos = Positions::Home.pos().clone();
+        let (width, height) = (40, 30);
+        let (min, max) = pos.bounds(width, height);
+        assert_eq!(pos.screen_to_complex(0, 0, width, height), min);
+        assert_eq!(pos.screen_to_complex(39, 29, width, height), max);
+    }
+
+    #[test]
+    fn bounds_cover_rotated_corners() {
+        let pos = Positions::Home.pos().clone().rotation(0.6);
+        let (width, height) = (40, 30);
+        let (min, max) = pos.bounds(width, height);
+        for (x, y) in [(0, 0), (39, 0), (0, 29), (39, 29), (20, 15)] {
+            let c = pos.screen_to_complex(x, y, width, height);
+            assert!(min.re <= c.re && c.re <= max.re);
+            assert!(min.im <= c.im && c.im <= max.im);
+        }
+    }
+
+    fn arrival_frames(from: &Position, to: &Position, limit_scale: f64) -> [u32; 4] {
+        let mut pos = from.clone();
+        let mut arrived = [None; 4];
+        for frame in 1..10_000 {
+            let reached = pos.make_step(to, Point::new(10.0, 10.0), 0.2, limit_scale);
+            let flags = [
+                pos.point == to.point,
+                pos.zoom == to.zoom,
+                pos.limit == to.limit,
+                reached,
+            ];
+            for (arrived, flag) in arrived.iter_mut().zip(flags) {
+                if flag {
+                    arrived.get_or_insert(frame);
+                }
+            }
+            if reached {
+                break;
+            }
+        }
+        arrived.map(Option::unwrap)
+    }
+
+    #[test]
+    fn make_step_arrives_on_same_frame_with_asymmetric_distances() {
+        let from = Position::new(Point::new(-0.5, 0.0), 200.0, 150);
+        let far_point = Position::new(Point::new(0.3, -0.4), 400.0, 600);
+        let far_zoom = Position::new(Point::new(-0.49, 0.01), 1e9, 1200);
+        for to in [far_point, far_zoom] {
+            let [point, zoom, limit, reached] = arrival_frames(&from, &to, 0.0);
+            assert!(reached > 1);
+            assert_eq!(point, reached);
+            assert_eq!(zoom, reached);
+            assert_eq!(limit, reached);
+            let [point, zoom, limit, reached] = arrival_frames(&from, &to, 0.25);
+            assert_eq!(point, reached);
+            assert_eq!(zoom, reached);
+            assert!(limit <= reached);
+        }
+    }
+
+    #[test]
+    fn make_step_limit_scale_speeds_up_limit() {
+        let from = Position::new(Point::new(-0.5, 0.0), 200.0, 150);
+        let to = Position::new(Point::new(-0.5, 0.0), 1e9, 100_000);
+        let step = |limit_scale| {
+            let mut pos = from.clone();
+            pos.make_step(&to, Point::new(10.0, 10.0), 0.2, limit_scale);
+            pos.limit
+        };
+        assert!(step(50.0) > step(0.0));
+    }
+
+    /// Deterministic stream of floats in `[0, 1)`.
+    fn sampler(mut seed: u64) -> impl FnMut() -> f64 {
+        move || {
+            seed = seed.wrapping_add(1);
+            splitmix64(seed) as f64 / 2f64.powi(64)
+        }
+    }
+
+    #[test]
+    fn compute_iterations_matches_reference_for_escaping_points() {
+        use crate::testutils::reference_escape;
+
+        let mut next = sampler(0x5EED);
+        let mut escaping = 0;
+        for i in 0..20_000 {
+            // Every other point comes from seahorse valley, where escaping
+            // orbits run long and sit right next to the cardioid check.
+            let (center, size) = if i % 2 == 0 {
+                (Complex64::new(-0.75, 0.0), 3.0)
+            } else {
+                (Complex64::new(-0.7435, 0.1271), 0.01)
+            };
+            let offset = Complex64::new(next() - 0.5, next() - 0.5) * size;
+            let c = center + offset;
+            let limit = 1 + (next() * 2000.0) as u32;
+            let expected = reference_escape(c, limit);
+            if !expected.is_infinite() {
+                escaping += 1;
+                assert_eq!(
+                    c.compute_iterations(limit),
+                    expected,
+                    "c = {c}, limit = {limit}"
+                );
+            }
+        }
+        assert!(escaping > 5_000);
+    }
+
+    #[test]
+    fn complex32_build_is_close_to_complex64() {
+        let pos = Positions::Home.pos().clone();
+        let (width, height) = (64, 48);
+        let mut expected = IterationMatrix::new(width, height);
+        expected.build_image(&pos, |iter| iter, Default::default());
+        let mut actual = IterationMatrix::new(width, height);
+        actual.build_map(
+            &pos,
+            |c| Complex32::new(c.re as f32, c.im as f32).compute_iterations(pos.limit),
+            Default::default(),
+        );
+        let same = actual
+            .values()
+            .zip(expected.values())
+            .filter(|(a, b)| a == b);
+        assert!(same.count() * 100 >= (width * height) as usize * 99);
+
+        let c = Complex64::new(-0.7436, 0.1318);
+        let narrow = Complex32::new(c.re as f32, c.im as f32);
+        let (wide, narrow) = (
+            c.compute_distance_estimate(pos.limit, 1e6).unwrap(),
+            narrow.compute_distance_estimate(pos.limit, 1e6).unwrap(),
+        );
+        assert!((wide - narrow).abs() <= wide * 1e-2);
+    }
+
+    #[test]
+    fn batch_matches_scalar_iterations() {
+        let mut next = sampler(0xBA7C);
+        for len in (0..=9).chain([LANES * 64 + 3]) {
+            let points: Vec<_> = (0..len)
+                .map(|_| Complex64::new(next() * 3.0 - 2.25, next() * 3.0 - 1.5))
+                .collect();
+            let limit = 1 + (next() * 500.0) as u32;
+            let mut out = vec![Iteration::Finite(u32::MAX); len];
+            compute_iterations_batch(&points, limit, &mut out);
+            for (point, iter) in points.iter().zip(out) {
+                assert_eq!(iter, point.compute_iterations(limit), "c = {point}");
+            }
         }
     }
+
+    #[test]
+    fn batched_build_matches_per_point_build() {
+        let pos = Positions::Elephant.pos().clone().rotation(0.3);
+        let (width, height) = (37, 21);
+        let mut expected = IterationMatrix::new(width, height);
+        expected.build_map(
+            &pos,
+            |c| c.compute_iterations(pos.limit),
+            Default::default(),
+        );
+        let mut actual = IterationMatrix::new(width, height);
+        actual.build_image(&pos, |iter| iter, Default::default());
+        assert!(actual == expected);
+    }
+
+    #[test]
+    fn progress_is_reported_in_order_on_calling_thread() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let options = ParallelBuildMandelbrotSetOptions::with_workers(4).progress({
+            let reports = reports.clone();
+            move |fraction| {
+                let thread = std::thread::current().id();
+                reports.lock().unwrap().push((thread, fraction));
+            }
+        });
+        let mut matrix = IterationMatrix::new(64, 64);
+        matrix
+            .par_build_image(Positions::Home.pos(), |iter| iter, options)
+            .unwrap();
+        let reports = reports.lock().unwrap();
+        let caller = std::thread::current().id();
+        assert!(!reports.is_empty());
+        assert!(reports.iter().all(|&(thread, _)| thread == caller));
+        assert!(reports.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        assert_eq!(reports.last().unwrap().1, 1.0);
+    }
 }
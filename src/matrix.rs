@@ -1,9 +1,13 @@
 use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
     marker::PhantomData,
     ops::{Deref, DerefMut, Index, IndexMut},
 };
 
-use crate::utils::{CrossJoin, TupleMapper};
+use crate::utils::{
+    par_for_each_chunk_mut, par_map_chunks, CrossJoin, PipelineResult, TupleMapper,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Matrix<T, V>
@@ -81,6 +85,172 @@ where
         self.indexes().zip(self.values())
     }
 
+    pub fn row(&self, y: u32) -> Option<&[T]> {
+        if y < self.height {
+            let start = self.data_index(0, y);
+            Some(&self.data[start..start + self.width as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).filter_map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: u32) -> impl Iterator<Item = &T> {
+        let (start, len) = if x < self.width {
+            (x as usize, self.height as usize)
+        } else {
+            (0, 0)
+        };
+        self.data
+            .get(start..)
+            .unwrap_or(&[])
+            .iter()
+            .step_by(self.width.max(1) as usize)
+            .take(len)
+    }
+
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Option<VecMatrix<T>>
+    where
+        T: Clone,
+    {
+        self.view(x, y, width, height).map(|view| view.to_matrix())
+    }
+
+    pub fn view(&self, x: u32, y: u32, width: u32, height: u32) -> Option<SubView<'_, T>> {
+        if !self.contains_rect(x, y, width, height) {
+            return None;
+        }
+        Some(SubView {
+            data: &self.data,
+            stride: self.width,
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Splits the matrix into `tile_width` x `tile_height` views in row-major
+    /// order. Tiles on the right and bottom edges may be smaller.
+    pub fn tiles(
+        &self,
+        tile_width: u32,
+        tile_height: u32,
+    ) -> impl Iterator<Item = (u32, u32, SubView<'_, T>)> {
+        let (tile_width, tile_height) = (tile_width.max(1), tile_height.max(1));
+        (0..self.height)
+            .step_by(tile_height as usize)
+            .cross_join((0..self.width).step_by(tile_width as usize))
+            .map(move |(y, x)| {
+                let width = tile_width.min(self.width - x);
+                let height = tile_height.min(self.height - y);
+                let view = SubView {
+                    data: &self.data,
+                    stride: self.width,
+                    x,
+                    y,
+                    width,
+                    height,
+                };
+                (x, y, view)
+            })
+    }
+
+    pub fn row_submatrix(&self, y: u32, height: u32) -> Option<Matrix<T, &[T]>> {
+        if !self.contains_rect(0, y, self.width, height) {
+            return None;
+        }
+        let start = self.data_index(0, y);
+        let end = start + self.width as usize * height as usize;
+        Some(Matrix::from_raw(self.width, height, &self.data[start..end]))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> VecMatrix<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        let data = self.data.iter().map(f).collect();
+        Matrix::from_raw(self.width, self.height, data)
+    }
+
+    pub fn par_map<U, F>(&self, f: F, workers: Option<u32>) -> PipelineResult<VecMatrix<U>>
+    where
+        T: Sync,
+        U: Send,
+        F: FnMut(&T) -> U + Send + Clone,
+    {
+        let data = par_map_chunks(&self.data, self.width as usize, f, workers)?;
+        Ok(Matrix::from_raw(self.width, self.height, data))
+    }
+
+    pub fn transpose(&self) -> VecMatrix<T>
+    where
+        T: Clone,
+    {
+        let mut data = Vec::with_capacity(self.data.len());
+        for x in 0..self.width {
+            data.extend(self.column(x).cloned());
+        }
+        Matrix::from_raw(self.height, self.width, data)
+    }
+
+    pub fn resize_nearest(&self, width: u32, height: u32) -> VecMatrix<T>
+    where
+        T: Clone,
+    {
+        if self.data.is_empty() {
+            return VecMatrix::from_raw(0, 0, Vec::new());
+        }
+        VecMatrix::from_fn(width, height, |x, y| {
+            let src_x = (x as u64 * self.width as u64 / width as u64) as u32;
+            let src_y = (y as u64 * self.height as u64 / height as u64) as u32;
+            self.get(src_x, src_y).clone()
+        })
+    }
+
+    pub fn diff_regions<W>(
+        &self,
+        other: &Matrix<T, W>,
+        tile: u32,
+    ) -> Result<Vec<(u32, u32, u32, u32)>, DimensionMismatch>
+    where
+        T: PartialEq,
+        W: Deref<Target = [T]>,
+    {
+        if self.size() != other.size() {
+            return Err(DimensionMismatch {
+                expected: self.size(),
+                found: other.size(),
+            });
+        }
+        let tile = tile.max(1);
+        let mut regions = Vec::new();
+        for y in (0..self.height).step_by(tile as usize) {
+            let height = tile.min(self.height - y);
+            for x in (0..self.width).step_by(tile as usize) {
+                let width = tile.min(self.width - x);
+                let changed = (y..y + height).any(|y| {
+                    let range = self.data_index(x, y)..self.data_index(x + width, y);
+                    self.data[range.clone()] != other.data[range]
+                });
+                if changed {
+                    regions.push((x, y, width, height));
+                }
+            }
+        }
+        Ok(regions)
+    }
+
+    fn contains_rect(&self, x: u32, y: u32, width: u32, height: u32) -> bool {
+        x.checked_add(width)
+            .is_some_and(|right| right <= self.width)
+            && y.checked_add(height)
+                .is_some_and(|bottom| bottom <= self.height)
+    }
+
     fn data_index(&self, x: u32, y: u32) -> usize {
         y as usize * self.width as usize + x as usize
     }
@@ -128,7 +298,10 @@ where
 
     pub fn set_checked(&mut self, x: u32, y: u32, value: T) -> Result<(), T> {
         match self.get_checked_mut(x, y) {
-            Some(item) => Ok(*item = value),
+            Some(item) => {
+                *item = value;
+                Ok(())
+            }
             None => Err(value),
         }
     }
@@ -141,12 +314,178 @@ where
         self.indexes().zip(self.values_mut())
     }
 
+    pub fn par_for_each_mut<F>(&mut self, f: F, workers: Option<u32>) -> PipelineResult<()>
+    where
+        T: Send,
+        F: Fn((u32, u32), &mut T) + Sync,
+    {
+        let width = self.width as usize;
+        par_for_each_chunk_mut(
+            &mut self.data,
+            width,
+            |index, value| {
+                let index = ((index % width) as u32, (index / width) as u32);
+                f(index, value)
+            },
+            workers,
+        )
+    }
+
+    pub fn flip_horizontal(&mut self) {
+        if self.width == 0 {
+            return;
+        }
+        for row in self.data.chunks_exact_mut(self.width as usize) {
+            row.reverse();
+        }
+    }
+
+    pub fn flip_vertical(&mut self) {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        for y in 0..height / 2 {
+            let (top, bottom) = self.data.split_at_mut((height - y - 1) * width);
+            top[y * width..(y + 1) * width].swap_with_slice(&mut bottom[..width]);
+        }
+    }
+
+    /// Moves every value so that `(x, y)` receives the old value at
+    /// `(x + dx, y + dy)`. Returns the rectangles `(x, y, width, height)` of
+    /// the exposed area, which keeps stale values.
+    pub fn shift(&mut self, dx: i64, dy: i64) -> Vec<(u32, u32, u32, u32)> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        if dx.unsigned_abs() >= width as u64 || dy.unsigned_abs() >= height as u64 {
+            return vec![(0, 0, self.width, self.height)];
+        }
+        let offset = dy * width + dx;
+        let indexes: Box<dyn Iterator<Item = i64>> = if offset > 0 {
+            Box::new(0..width * height)
+        } else {
+            Box::new((0..width * height).rev())
+        };
+        for index in indexes {
+            let (x, y) = (index % width + dx, index / width + dy);
+            if (0..width).contains(&x) && (0..height).contains(&y) {
+                self.data.swap(index as usize, (index + offset) as usize);
+            }
+        }
+        let mut exposed = Vec::new();
+        let columns = dx.unsigned_abs() as u32;
+        let rows = dy.unsigned_abs() as u32;
+        let column_x = if dx > 0 { self.width - columns } else { 0 };
+        if columns > 0 {
+            exposed.push((column_x, 0, columns, self.height));
+        }
+        if rows > 0 {
+            let row_y = if dy > 0 { self.height - rows } else { 0 };
+            let row_x = if dx < 0 { columns } else { 0 };
+            exposed.push((row_x, row_y, self.width - columns, rows));
+        }
+        exposed
+    }
+
     pub fn clear(&mut self)
     where
         T: Default,
     {
         self.data.fill_with(Default::default);
     }
+
+    /// Fills the part of the rectangle that lies inside of the matrix.
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, value: T)
+    where
+        T: Clone,
+    {
+        let (x0, x1) = (x.min(self.width), x.saturating_add(width).min(self.width));
+        let (y0, y1) = (
+            y.min(self.height),
+            y.saturating_add(height).min(self.height),
+        );
+        let stride = self.width as usize;
+        for y in y0 as usize..y1 as usize {
+            self.data[y * stride + x0 as usize..y * stride + x1 as usize].fill(value.clone());
+        }
+    }
+
+    /// Draws a Bresenham line including both ends. The segment is clipped to
+    /// the matrix first, so far away endpoints cost nothing.
+    pub fn draw_line(&mut self, from: (i64, i64), to: (i64, i64), value: T)
+    where
+        T: Clone,
+    {
+        let Some((from, to)) = self.clip_line(from, to) else {
+            return;
+        };
+        let (mut x, mut y) = from;
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let (sx, sy) = ((to.0 - x).signum(), (to.1 - y).signum());
+        let mut error = dx + dy;
+        loop {
+            let _ = self.set_checked(x as u32, y as u32, value.clone());
+            if (x, y) == to {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Liang-Barsky clipping of the segment to the area covered by the
+    /// matrix pixels. Returns endpoints that lie inside of the matrix, or
+    /// `None` if the segment misses it.
+    fn clip_line(&self, from: (i64, i64), to: (i64, i64)) -> Option<((i64, i64), (i64, i64))> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let (max_x, max_y) = (self.width as f64 - 0.5, self.height as f64 - 0.5);
+        let (x0, y0) = (from.0 as f64, from.1 as f64);
+        let (dx, dy) = (to.0 as f64 - x0, to.1 as f64 - y0);
+        // Each edge is `(is_horizontal, coordinate)`. Computing the clipped
+        // point from its edge keeps that axis exact even for huge segments,
+        // where `t` alone has too few bits.
+        let (mut enter, mut exit) = ((0f64, None), (1f64, None));
+        for (p, q, edge) in [
+            (-dx, x0 + 0.5, (false, -0.5)),
+            (dx, max_x - x0, (false, max_x)),
+            (-dy, y0 + 0.5, (true, -0.5)),
+            (dy, max_y - y0, (true, max_y)),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else if p < 0.0 {
+                if q / p > enter.0 {
+                    enter = (q / p, Some(edge));
+                }
+            } else if q / p < exit.0 {
+                exit = (q / p, Some(edge));
+            }
+        }
+        if enter.0 > exit.0 {
+            return None;
+        }
+        let clip = |edge: Option<(bool, f64)>, end| {
+            let (x, y) = match edge {
+                None => return end,
+                Some((false, x)) => (x, y0 + (x - x0) * dy / dx),
+                Some((true, y)) => (x0 + (y - y0) * dx / dy, y),
+            };
+            (
+                x.round().clamp(0.0, max_x - 0.5) as i64,
+                y.round().clamp(0.0, max_y - 0.5) as i64,
+            )
+        };
+        Some((clip(enter.1, from), clip(exit.1, to)))
+    }
 }
 
 impl<T, V> Index<(u32, u32)> for Matrix<T, V>
@@ -171,6 +510,89 @@ where
 
 pub type VecMatrix<T> = Matrix<T, Vec<T>>;
 
+impl<'a, T> Matrix<T, &'a [T]> {
+    pub fn from_slice(width: u32, height: u32, slice: &'a [T]) -> Result<Self, &'a [T]> {
+        Self::try_from_raw(width, height, slice)
+    }
+}
+
+impl<'a, T> Matrix<T, &'a mut [T]> {
+    /// Wraps an existing buffer, e.g. a window framebuffer, to build into it
+    /// without copying. Returns the slice back if its length doesn't match.
+    pub fn from_slice_mut(
+        width: u32,
+        height: u32,
+        slice: &'a mut [T],
+    ) -> Result<Self, &'a mut [T]> {
+        Self::try_from_raw(width, height, slice)
+    }
+}
+
+/// Borrowed rectangular region of a [`Matrix`].
+#[derive(Debug)]
+pub struct SubView<'a, T> {
+    data: &'a [T],
+    stride: u32,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl<T> Clone for SubView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SubView<'_, T> {}
+
+impl<'a, T> SubView<'a, T> {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn get_checked(&self, x: u32, y: u32) -> Option<&'a T> {
+        if x < self.width && y < self.height {
+            self.row(y).map(|row| &row[x as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, y: u32) -> Option<&'a [T]> {
+        if y >= self.height {
+            return None;
+        }
+        let start = (self.y + y) as usize * self.stride as usize + self.x as usize;
+        Some(&self.data[start..start + self.width as usize])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let view = *self;
+        (0..self.height).filter_map(move |y| view.row(y))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &'a T> {
+        self.rows().flatten()
+    }
+
+    pub fn to_matrix(&self) -> VecMatrix<T>
+    where
+        T: Clone,
+    {
+        Matrix::from_raw(self.width, self.height, self.values().cloned().collect())
+    }
+}
+
 impl<T> VecMatrix<T> {
     pub fn new_with<F>(width: u32, height: u32, f: F) -> Self
     where
@@ -188,4 +610,151 @@ impl<T> VecMatrix<T> {
     {
         Self::new_with(width, height, Default::default)
     }
+
+    /// Builds a matrix by calling `f(x, y)` for every cell in row-major order:
+    /// `x` runs from `0` to `width` within each row, rows go from `y = 0` down.
+    pub fn from_fn<F>(width: u32, height: u32, mut f: F) -> Self
+    where
+        F: FnMut(u32, u32) -> T,
+    {
+        let len = width as usize * height as usize;
+        let mut data = Vec::with_capacity(len);
+        for y in 0..height {
+            for x in 0..width {
+                data.push(f(x, y));
+            }
+        }
+        Self::from_raw(width, height, data)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DimensionMismatch {
+    pub expected: (u32, u32),
+    pub found: (u32, u32),
+}
+
+impl Display for DimensionMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (expected_width, expected_height) = self.expected;
+        let (found_width, found_height) = self.found;
+        write!(
+            f,
+            "expected {expected_width}x{expected_height} matrix, found {found_width}x{found_height}"
+        )
+    }
+}
+
+impl Error for DimensionMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_of_empty_matrix() {
+        let matrix = VecMatrix::<u8>::new(5, 0);
+        assert_eq!(matrix.column(3).count(), 0);
+        assert_eq!(matrix.column(7).count(), 0);
+        let matrix = VecMatrix::<u8>::new(0, 4);
+        assert_eq!(matrix.column(0).count(), 0);
+    }
+
+    fn non_square() -> VecMatrix<u8> {
+        VecMatrix::try_from_raw(3, 2, vec![0, 1, 2, 3, 4, 5]).unwrap()
+    }
+
+    #[test]
+    fn transpose_swaps_dimensions() {
+        let matrix = non_square();
+        let transposed = matrix.transpose();
+        assert_eq!((transposed.width(), transposed.height()), (2, 3));
+        for (x, y) in (0..3).flat_map(|x| (0..2).map(move |y| (x, y))) {
+            assert_eq!(transposed.get(y, x), matrix.get(x, y));
+        }
+    }
+
+    #[test]
+    fn flips_mirror_their_axis() {
+        let mut matrix = non_square();
+        matrix.flip_horizontal();
+        assert!(matrix == VecMatrix::try_from_raw(3, 2, vec![2, 1, 0, 5, 4, 3]).unwrap());
+        let mut matrix = non_square();
+        matrix.flip_vertical();
+        assert!(matrix == VecMatrix::try_from_raw(3, 2, vec![3, 4, 5, 0, 1, 2]).unwrap());
+    }
+
+    #[test]
+    fn from_slice_mut_writes_through() {
+        let mut buffer = [0u8; 6];
+        let mut matrix = Matrix::from_slice_mut(3, 2, &mut buffer).unwrap();
+        matrix[(2, 1)] = 7;
+        matrix.fill_rect(0, 0, 2, 1, 1);
+        assert_eq!(buffer, [1, 1, 0, 0, 0, 7]);
+        let mut short = [0u8; 5];
+        let rejected = Matrix::from_slice_mut(3, 2, &mut short).unwrap_err();
+        assert_eq!(rejected.len(), 5);
+    }
+
+    fn drawn(matrix: &VecMatrix<bool>) -> Vec<(u32, u32)> {
+        matrix
+            .pairs()
+            .filter(|&(_, &set)| set)
+            .map(|(point, _)| point)
+            .collect()
+    }
+
+    #[test]
+    fn draw_line_inside_keeps_bresenham() {
+        let mut matrix = VecMatrix::new(6, 4);
+        matrix.draw_line((0, 0), (5, 2), true);
+        let mut expected = vec![(0, 0), (1, 0), (2, 1), (3, 1), (4, 2), (5, 2)];
+        expected.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(drawn(&matrix), expected);
+    }
+
+    #[test]
+    fn draw_line_clips_far_endpoints() {
+        let mut matrix = VecMatrix::new(10, 3);
+        matrix.draw_line((-1 << 40, 1), (5, 1), true);
+        assert_eq!(drawn(&matrix), (0..=5).map(|x| (x, 1)).collect::<Vec<_>>());
+
+        let mut matrix = VecMatrix::new(10, 3);
+        matrix.draw_line((i64::MIN, 2), (i64::MAX, 2), true);
+        assert_eq!(drawn(&matrix), (0..10).map(|x| (x, 2)).collect::<Vec<_>>());
+
+        let mut matrix = VecMatrix::new(10, 3);
+        matrix.draw_line((i64::MIN, i64::MIN), (i64::MAX, i64::MAX), true);
+        assert!(!drawn(&matrix).is_empty());
+    }
+
+    #[test]
+    fn draw_line_clips_to_the_line() {
+        let mut matrix = VecMatrix::new(10, 5);
+        matrix.draw_line((-20, -10), (30, 15), true);
+        let points = drawn(&matrix);
+        for x in 0..10 {
+            assert_eq!(points.iter().filter(|point| point.0 == x).count(), 1);
+        }
+        for (x, y) in points {
+            assert!((y as f64 - x as f64 / 2.0).abs() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn draw_line_outside_draws_nothing() {
+        let mut matrix = VecMatrix::new(10, 5);
+        matrix.draw_line((-5, -1), (20, -1), true);
+        matrix.draw_line((-5, 3), (3, -5), true);
+        matrix.draw_line((12, 0), (12, 4), true);
+        assert!(drawn(&matrix).is_empty());
+        VecMatrix::new(0, 0).draw_line((0, 0), (1 << 50, 1 << 50), true);
+    }
+
+    #[test]
+    fn column_walks_rows() {
+        let matrix = VecMatrix::try_from_raw(3, 2, vec![0, 1, 2, 3, 4, 5]).unwrap();
+        assert!(matrix.column(1).eq(&[1, 4]));
+        assert_eq!(matrix.column(3).count(), 0);
+    }
 }
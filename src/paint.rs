@@ -1,8 +1,25 @@
-use std::{f64::consts::PI, ops::Deref};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    error::Error,
+    f64::consts::PI,
+    fmt::{self, Display, Formatter},
+    ops::Deref,
+    str::FromStr,
+};
 
-use crate::matrix::VecMatrix;
+use crate::{
+    mandelbrot::{Iteration, IterationMatrix},
+    matrix::{DimensionMismatch, VecMatrix},
+    utils::{normalize_name, PipelineResult},
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[u8; 3]", into = "[u8; 3]")
+)]
 pub struct Rgb {
     pub r: u8,
     pub g: u8,
@@ -12,17 +29,138 @@ pub struct Rgb {
 impl Rgb {
     pub const BLACK: Rgb = Rgb::new(0, 0, 0);
     pub const WHITE: Rgb = Rgb::new(255, 255, 255);
+    pub const RED: Rgb = Rgb::new(255, 0, 0);
+    pub const GREEN: Rgb = Rgb::new(0, 255, 0);
+    pub const BLUE: Rgb = Rgb::new(0, 0, 255);
+    pub const CYAN: Rgb = Rgb::new(0, 255, 255);
+    pub const MAGENTA: Rgb = Rgb::new(255, 0, 255);
+    pub const YELLOW: Rgb = Rgb::new(255, 255, 0);
+    pub const GRAY: Rgb = Rgb::new(128, 128, 128);
 
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
 
+    /// Rounds each channel and clamps it to `[0, 255]`.
+    pub fn from_f64(r: f64, g: f64, b: f64) -> Self {
+        Self::new(round_channel(r), round_channel(g), round_channel(b))
+    }
+
     pub const fn from_rgba(value: u64) -> Self {
         let r = ((value >> 24) & 0xFF) as u8;
         let g = ((value >> 16) & 0xFF) as u8;
         let b = ((value >> 8) & 0xFF) as u8;
         Self::new(r, g, b)
     }
+
+    pub fn from_hex(hex: &str) -> Result<Self, ParseRgbError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ParseRgbError::InvalidDigit(c));
+        }
+        let digit = |i: usize| u8::from_str_radix(&digits[i..i + 1], 16).unwrap();
+        match digits.len() {
+            3 => Ok(Self::new(digit(0) * 0x11, digit(1) * 0x11, digit(2) * 0x11)),
+            6 => Ok(Self::new(
+                digit(0) << 4 | digit(1),
+                digit(2) << 4 | digit(3),
+                digit(4) << 4 | digit(5),
+            )),
+            len => Err(ParseRgbError::InvalidLength(len)),
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.to_string()
+    }
+
+    pub const fn with_alpha(&self, a: u8) -> Rgba {
+        Rgba::new(self.r, self.g, self.b, a)
+    }
+
+    pub fn to_hsv(&self) -> Hsv {
+        let r = self.r as f64 / 255.0;
+        let g = self.g as f64 / 255.0;
+        let b = self.b as f64 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let s = if max == 0.0 { 0.0 } else { delta / max };
+        Hsv::new(h, s, max)
+    }
+
+    pub fn average<I>(colors: I) -> Self
+    where
+        I: IntoIterator<Item = Rgb>,
+    {
+        let mut count = 0u32;
+        let (mut r, mut g, mut b) = (0u32, 0u32, 0u32);
+        for color in colors {
+            count += 1;
+            r += color.r as u32;
+            g += color.g as u32;
+            b += color.b as u32;
+        }
+        if count == 0 {
+            return Self::default();
+        }
+        let average = |sum: u32| ((sum + count / 2) / count) as u8;
+        Self::new(average(r), average(g), average(b))
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = ParseRgbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseRgbError {
+    InvalidLength(usize),
+    InvalidDigit(char),
+}
+
+impl Display for ParseRgbError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRgbError::InvalidLength(len) => {
+                write!(f, "expected 3 or 6 hex digits, found {len}")
+            }
+            ParseRgbError::InvalidDigit(c) => write!(f, "invalid hex digit {c:?}"),
+        }
+    }
+}
+
+impl Error for ParseRgbError {}
+
+impl From<[u8; 3]> for Rgb {
+    fn from([r, g, b]: [u8; 3]) -> Self {
+        Self::new(r, g, b)
+    }
+}
+
+impl From<Rgb> for [u8; 3] {
+    fn from(val: Rgb) -> Self {
+        [val.r, val.g, val.b]
+    }
 }
 
 impl Default for Rgb {
@@ -31,8 +169,358 @@ impl Default for Rgb {
     }
 }
 
+impl From<Rgba> for Rgb {
+    fn from(value: Rgba) -> Self {
+        Self::new(value.r, value.g, value.b)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[u8; 4]", into = "[u8; 4]")
+)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Rgba {
+    pub const TRANSPARENT: Rgba = Rgba::new(0, 0, 0, 0);
+
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn over(self, background: Rgba) -> Rgba {
+        let (a_src, a_dst) = (self.a as u32, background.a as u32);
+        let a_dst = a_dst * (255 - a_src);
+        let a = a_src * 255 + a_dst;
+        if a == 0 {
+            return Self::TRANSPARENT;
+        }
+        let blend = |src: u8, dst: u8| {
+            let value = src as u32 * a_src * 255 + dst as u32 * a_dst;
+            ((value + a / 2) / a) as u8
+        };
+        Self::new(
+            blend(self.r, background.r),
+            blend(self.g, background.g),
+            blend(self.b, background.b),
+            ((a + 127) / 255) as u8,
+        )
+    }
+
+    pub fn average<I>(colors: I) -> Self
+    where
+        I: IntoIterator<Item = Rgba>,
+    {
+        let mut count = 0u32;
+        let (mut r, mut g, mut b, mut a) = (0u32, 0u32, 0u32, 0u32);
+        for color in colors {
+            count += 1;
+            r += color.r as u32 * color.a as u32;
+            g += color.g as u32 * color.a as u32;
+            b += color.b as u32 * color.a as u32;
+            a += color.a as u32;
+        }
+        if a == 0 {
+            return Self::TRANSPARENT;
+        }
+        let average = |sum: u32| ((sum + a / 2) / a) as u8;
+        Self::new(
+            average(r),
+            average(g),
+            average(b),
+            ((a + count / 2) / count) as u8,
+        )
+    }
+}
+
+impl From<[u8; 4]> for Rgba {
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Self::new(r, g, b, a)
+    }
+}
+
+impl From<Rgba> for [u8; 4] {
+    fn from(val: Rgba) -> Self {
+        [val.r, val.g, val.b, val.a]
+    }
+}
+
+impl From<Rgb> for Rgba {
+    fn from(value: Rgb) -> Self {
+        value.with_alpha(u8::MAX)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsv {
+    pub h: f64,
+    pub s: f64,
+    pub v: f64,
+}
+
+impl Hsv {
+    pub const fn new(h: f64, s: f64, v: f64) -> Self {
+        Self { h, s, v }
+    }
+
+    pub fn to_rgb(&self) -> Rgb {
+        let h = self.h.rem_euclid(360.0) / 60.0;
+        let s = self.s.clamp(0.0, 1.0);
+        let v = self.v.clamp(0.0, 1.0);
+        let c = v * s;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = v - c;
+        Rgb::from_f64((r + m) * 255.0, (g + m) * 255.0, (b + m) * 255.0)
+    }
+}
+
+fn round_channel(value: f64) -> u8 {
+    value.round().clamp(0.0, u8::MAX as f64) as u8
+}
+
 pub type RgbImage = VecMatrix<Rgb>;
 
+pub type RgbaImage = VecMatrix<Rgba>;
+
+impl RgbImage {
+    pub fn resize_bilinear(&self, width: u32, height: u32) -> RgbImage {
+        if self.as_slice().is_empty() {
+            return RgbImage::new(0, 0);
+        }
+        RgbImage::from_fn(width, height, |x, y| {
+            let (x0, x1, fx) = bilinear_sample(x, width, self.width());
+            let (y0, y1, fy) = bilinear_sample(y, height, self.height());
+            let top = lerp_rgb(*self.get(x0, y0), *self.get(x1, y0), fx);
+            let bottom = lerp_rgb(*self.get(x0, y1), *self.get(x1, y1), fx);
+            lerp_rgb(top, bottom, fy)
+        })
+    }
+
+    /// Averages every `factor x factor` block into one pixel. When the size is
+    /// not divisible by `factor`, the last row and column average the partial
+    /// blocks.
+    pub fn downsample_box(&self, factor: u32) -> RgbImage {
+        let factor = factor.max(1);
+        let width = self.width().div_ceil(factor);
+        let height = self.height().div_ceil(factor);
+        RgbImage::from_fn(width, height, |x, y| {
+            let (x0, y0) = (x * factor, y * factor);
+            let block_width = factor.min(self.width() - x0);
+            let block_height = factor.min(self.height() - y0);
+            let mut sum = [0u64; 3];
+            for y in y0..y0 + block_height {
+                for x in x0..x0 + block_width {
+                    let color = self.get(x, y);
+                    sum[0] += color.r as u64;
+                    sum[1] += color.g as u64;
+                    sum[2] += color.b as u64;
+                }
+            }
+            let count = block_width as u64 * block_height as u64;
+            let [r, g, b] = sum.map(|v| ((v + count / 2) / count) as u8);
+            Rgb::new(r, g, b)
+        })
+    }
+
+    /// Applies a 3x3 kernel, repeating the edge pixels beyond the borders.
+    pub fn convolve(&self, kernel: &[[f64; 3]; 3]) -> RgbImage {
+        let (width, height) = (self.width() as i64, self.height() as i64);
+        RgbImage::from_fn(self.width(), self.height(), |x, y| {
+            let mut sum = [0.0; 3];
+            for (dy, row) in (-1..=1).zip(kernel) {
+                for (dx, weight) in (-1..=1).zip(row) {
+                    let sx = (x as i64 + dx).clamp(0, width - 1) as u32;
+                    let sy = (y as i64 + dy).clamp(0, height - 1) as u32;
+                    let color = self.get(sx, sy);
+                    sum[0] += color.r as f64 * weight;
+                    sum[1] += color.g as f64 * weight;
+                    sum[2] += color.b as f64 * weight;
+                }
+            }
+            let [r, g, b] = sum.map(|v| v.round().clamp(0.0, u8::MAX as f64) as u8);
+            Rgb::new(r, g, b)
+        })
+    }
+
+    pub fn color_histogram(&self) -> HashMap<Rgb, u32> {
+        let mut histogram = HashMap::new();
+        for &color in self.values() {
+            *histogram.entry(color).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// The `n` most frequent colors with their counts, most frequent first.
+    pub fn dominant_colors(&self, n: usize) -> Vec<(Rgb, u32)> {
+        let mut colors: Vec<_> = self.color_histogram().into_iter().collect();
+        colors.sort_by_key(|&(color, count)| (Reverse(count), <[u8; 3]>::from(color)));
+        colors.truncate(n);
+        colors
+    }
+
+    pub fn box_blur(&self) -> RgbImage {
+        self.convolve(&BOX_BLUR_KERNEL)
+    }
+
+    pub fn gaussian_blur(&self) -> RgbImage {
+        self.convolve(&GAUSSIAN_BLUR_KERNEL)
+    }
+}
+
+pub const BOX_BLUR_KERNEL: [[f64; 3]; 3] = [[1.0 / 9.0; 3]; 3];
+
+pub const GAUSSIAN_BLUR_KERNEL: [[f64; 3]; 3] = [
+    [1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0],
+    [2.0 / 16.0, 4.0 / 16.0, 2.0 / 16.0],
+    [1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0],
+];
+
+impl RgbaImage {
+    pub fn composite_over(&mut self, top: &RgbaImage) -> Result<(), DimensionMismatch> {
+        if (self.width(), self.height()) != (top.width(), top.height()) {
+            return Err(DimensionMismatch {
+                expected: (self.width(), self.height()),
+                found: (top.width(), top.height()),
+            });
+        }
+        for (background, color) in self.values_mut().zip(top.values()) {
+            *background = color.over(*background);
+        }
+        Ok(())
+    }
+}
+
+/// Blends each animation frame with the previous one to reduce flicker
+/// between frames. `alpha` is the weight of the new frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemporalBlend {
+    alpha: f64,
+    previous: Option<RgbImage>,
+}
+
+impl TemporalBlend {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            previous: None,
+        }
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn blend(
+        &self,
+        previous: &RgbImage,
+        current: &RgbImage,
+    ) -> Result<RgbImage, DimensionMismatch> {
+        if previous.size() != current.size() {
+            return Err(DimensionMismatch {
+                expected: previous.size(),
+                found: current.size(),
+            });
+        }
+        let frac = (self.alpha * BILINEAR_ONE as f64).round() as u32;
+        Ok(RgbImage::from_fn(
+            current.width(),
+            current.height(),
+            |x, y| lerp_rgb(*previous.get(x, y), *current.get(x, y), frac),
+        ))
+    }
+
+    /// Blends `frame` with the frame passed to the previous call. The first
+    /// frame, and any frame of a different size, is returned unchanged.
+    pub fn apply(&mut self, frame: &RgbImage) -> RgbImage {
+        let blended = self
+            .previous
+            .as_ref()
+            .and_then(|previous| self.blend(previous, frame).ok())
+            .unwrap_or_else(|| frame.clone());
+        self.previous = Some(frame.clone());
+        blended
+    }
+
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Paints `iters` into `out` without allocating.
+///
+/// # Panics
+///
+/// Panics if the matrices differ in size.
+pub fn colorize_into<F>(iters: &IterationMatrix, out: &mut RgbImage, map: F)
+where
+    F: Fn(Iteration) -> Rgb,
+{
+    assert_colorize_size(iters, out);
+    for (&iter, color) in iters.values().zip(out.values_mut()) {
+        *color = map(iter);
+    }
+}
+
+/// Parallel version of [`colorize_into`].
+///
+/// # Panics
+///
+/// Panics if the matrices differ in size.
+pub fn par_colorize_into<F>(
+    iters: &IterationMatrix,
+    out: &mut RgbImage,
+    map: F,
+    workers: Option<u32>,
+) -> PipelineResult<()>
+where
+    F: Fn(Iteration) -> Rgb + Sync,
+{
+    assert_colorize_size(iters, out);
+    out.par_for_each_mut(|(x, y), color| *color = map(*iters.get(x, y)), workers)
+}
+
+fn assert_colorize_size(iters: &IterationMatrix, out: &RgbImage) {
+    assert_eq!(
+        iters.size(),
+        out.size(),
+        "Iteration matrix and image sizes differ"
+    );
+}
+
+const BILINEAR_ONE: u32 = 256;
+
+fn bilinear_sample(dest: u32, dest_len: u32, src_len: u32) -> (u32, u32, u32) {
+    let center = (2 * dest as u64 + 1) * src_len as u64 * BILINEAR_ONE as u64;
+    let pos = (center / (2 * dest_len as u64)).saturating_sub(BILINEAR_ONE as u64 / 2);
+    let index = ((pos / BILINEAR_ONE as u64) as u32).min(src_len - 1);
+    let frac = (pos % BILINEAR_ONE as u64) as u32;
+    (index, (index + 1).min(src_len - 1), frac)
+}
+
+fn lerp_rgb(a: Rgb, b: Rgb, frac: u32) -> Rgb {
+    let lerp = |a: u8, b: u8| {
+        let value = a as u32 * (BILINEAR_ONE - frac) + b as u32 * frac;
+        ((value + BILINEAR_ONE / 2) / BILINEAR_ONE) as u8
+    };
+    Rgb::new(lerp(a.r, b.r), lerp(a.g, b.g), lerp(a.b, b.b))
+}
+
 pub trait Wave {
     type Output;
 
@@ -40,6 +528,7 @@ pub trait Wave {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Harmonic {
     pub ampl: f64,
     pub freq: f64,
@@ -82,6 +571,7 @@ impl Wave for Harmonic {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polyharmonic<T>
 where
     T: Deref<Target = [Harmonic]>,
@@ -130,6 +620,16 @@ where
     pub const fn new(r: R, g: G, b: B) -> Self {
         Self { r, g, b }
     }
+
+    /// Samples `len` colors at `index * scale`, each averaged over
+    /// `subsamples` points spread across its step to avoid aliasing.
+    pub fn sample_averaged(&self, len: usize, scale: f64, subsamples: u32) -> Vec<Rgb> {
+        (0..len)
+            .map(|index| {
+                Rgb::average(subsample_points(index, scale, subsamples).map(|x| self.wave(x)))
+            })
+            .collect()
+    }
 }
 
 impl<R, G, B> Wave for RgbWave<R, G, B>
@@ -156,6 +656,8 @@ where
     pub wave: T,
     pub min: f64,
     pub max: f64,
+    /// Rounds to the nearest value instead of truncating.
+    pub rounding: bool,
 }
 
 impl<T> WaveU8<T>
@@ -163,10 +665,38 @@ where
     T: Wave<Output = f64>,
 {
     pub const fn new(wave: T, min: f64, max: f64) -> Self {
-        Self { wave, min, max }
+        Self {
+            wave,
+            min,
+            max,
+            rounding: false,
+        }
+    }
+
+    pub const fn rounding(mut self, rounding: bool) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Like [`RgbWave::sample_averaged`], for a single channel.
+    pub fn sample_averaged(&self, len: usize, scale: f64, subsamples: u32) -> Vec<u8> {
+        (0..len)
+            .map(|index| {
+                let (sum, count) = subsample_points(index, scale, subsamples)
+                    .map(|x| self.wave(x) as u32)
+                    .fold((0, 0), |(sum, count), y| (sum + y, count + 1));
+                (sum / count) as u8
+            })
+            .collect()
     }
 }
 
+fn subsample_points(index: usize, scale: f64, subsamples: u32) -> impl Iterator<Item = f64> {
+    let subsamples = subsamples.max(1);
+    let step = 1.0 / subsamples as f64;
+    (0..subsamples).map(move |i| (index as f64 + (i as f64 + 0.5) * step - 0.5) * scale)
+}
+
 impl<T> Wave for WaveU8<T>
 where
     T: Wave<Output = f64>,
@@ -176,11 +706,97 @@ where
     fn wave(&self, x: f64) -> Self::Output {
         let y = self.wave.wave(x);
         let norm_y = ((y - self.min) / (self.max - self.min)).clamp(0.0, 1.0);
-        (norm_y * 255.0) as u8
+        if self.rounding {
+            round_channel(norm_y * 255.0)
+        } else {
+            (norm_y * 255.0) as u8
+        }
+    }
+}
+
+pub trait ColorMap {
+    fn color(&self, value: u8) -> Rgb;
+}
+
+impl<F> ColorMap for F
+where
+    F: Fn(u8) -> Rgb,
+{
+    fn color(&self, value: u8) -> Rgb {
+        self(value)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReversedColorMap<C> {
+    pub color_map: C,
+}
+
+impl<C> ReversedColorMap<C> {
+    pub const fn new(color_map: C) -> Self {
+        Self { color_map }
+    }
+}
+
+impl<C> ColorMap for ReversedColorMap<C>
+where
+    C: ColorMap,
+{
+    fn color(&self, value: u8) -> Rgb {
+        self.color_map.color(u8::MAX - value)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShiftedColorMap<C> {
+    pub color_map: C,
+    pub offset: u8,
+}
+
+impl<C> ShiftedColorMap<C> {
+    pub const fn new(color_map: C, offset: u8) -> Self {
+        Self { color_map, offset }
+    }
+}
+
+impl<C> ColorMap for ShiftedColorMap<C>
+where
+    C: ColorMap,
+{
+    fn color(&self, value: u8) -> Rgb {
+        self.color_map.color(value.wrapping_add(self.offset))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GammaCorrected<C> {
+    pub color_map: C,
+    pub gamma: f64,
+}
+
+impl<C> GammaCorrected<C> {
+    pub const fn new(color_map: C, gamma: f64) -> Self {
+        Self { color_map, gamma }
+    }
+}
+
+impl<C> ColorMap for GammaCorrected<C>
+where
+    C: ColorMap,
+{
+    fn color(&self, value: u8) -> Rgb {
+        let color = self.color_map.color(value);
+        if self.gamma == 1.0 {
+            return color;
+        }
+        let exponent = self.gamma.recip();
+        let correct = |channel: u8| (255.0 * (channel as f64 / 255.0).powf(exponent)).round() as u8;
+        Rgb::new(correct(color.r), correct(color.g), correct(color.b))
     }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Palette {
     #[default]
     Original,
@@ -197,9 +813,57 @@ pub enum Palette {
     Tertiary1,
     Tertiary2,
     Neon,
+    Rainbow,
 }
 
+const PALETTES: [Palette; 15] = [
+    Palette::Original,
+    Palette::Fire,
+    Palette::BlackAndWhite,
+    Palette::ElectricBlue,
+    Palette::Toon,
+    Palette::Gold,
+    Palette::ClassicVga,
+    Palette::Cga1,
+    Palette::Cga2,
+    Palette::PrimaryRgb,
+    Palette::SecondaryCmy,
+    Palette::Tertiary1,
+    Palette::Tertiary2,
+    Palette::Neon,
+    Palette::Rainbow,
+];
+
 impl Palette {
+    pub fn all() -> impl Iterator<Item = Palette> {
+        PALETTES.into_iter()
+    }
+
+    pub fn from_name(name: &str) -> Option<Palette> {
+        let name = normalize_name(name);
+        Self::all().find(|palette| normalize_name(palette.name()) == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Original => "Original",
+            Palette::Fire => "Fire",
+            Palette::BlackAndWhite => "BlackAndWhite",
+            Palette::ElectricBlue => "ElectricBlue",
+            Palette::Toon => "Toon",
+            Palette::Gold => "Gold",
+            Palette::ClassicVga => "ClassicVga",
+            Palette::Cga1 => "Cga1",
+            Palette::Cga2 => "Cga2",
+            Palette::PrimaryRgb => "PrimaryRgb",
+            Palette::SecondaryCmy => "SecondaryCmy",
+            Palette::Tertiary1 => "Tertiary1",
+            Palette::Tertiary2 => "Tertiary2",
+            Palette::Neon => "Neon",
+            Palette::Rainbow => "Rainbow",
+        }
+    }
+
     pub fn get_color(&self, value: u8) -> Rgb {
         match *self {
             Palette::Original => original(value),
@@ -216,63 +880,245 @@ impl Palette {
             Palette::Tertiary1 => tertiary1(value),
             Palette::Tertiary2 => tertiary2(value),
             Palette::Neon => neon(value),
+            Palette::Rainbow => rainbow(value),
         }
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GradientPalette {
+    stops: Vec<(f64, Rgb)>,
+}
+
+impl GradientPalette {
+    pub fn new<I>(stops: I) -> Self
+    where
+        I: IntoIterator<Item = (f64, Rgb)>,
+    {
+        let mut stops: Vec<_> = stops
+            .into_iter()
+            .filter(|(stop, _)| stop.is_finite())
+            .map(|(stop, color)| (stop.clamp(0.0, 1.0), color))
+            .collect();
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self { stops }
+    }
+
+    pub fn stops(&self) -> &[(f64, Rgb)] {
+        &self.stops
+    }
+
+    pub fn get_color(&self, value: u8) -> Rgb {
+        let x = value as f64 / u8::MAX as f64;
+        let next = self.stops.partition_point(|&(stop, _)| stop < x);
+        match (self.stops.get(next.wrapping_sub(1)), self.stops.get(next)) {
+            (Some(&(from, a)), Some(&(to, b))) => {
+                let t = (x - from) / (to - from);
+                lerp_rgb(a, b, (t * BILINEAR_ONE as f64).round() as u32)
+            }
+            (Some(&(_, color)), None) | (None, Some(&(_, color))) => color,
+            (None, None) => Rgb::BLACK,
+        }
+    }
+}
+
+/// Precomputed colors indexed modulo their count.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColorTable {
+    colors: Vec<Rgb>,
+}
+
+impl ColorTable {
+    pub fn new(colors: Vec<Rgb>) -> Self {
+        Self { colors }
+    }
+
+    /// Samples `len` colors evenly across the whole range of `color_map`.
+    pub fn from_colormap<C>(color_map: &C, len: usize) -> Self
+    where
+        C: ColorMap,
+    {
+        let colors = (0..len)
+            .map(|index| color_map.color((index * 256 / len) as u8))
+            .collect();
+        Self { colors }
+    }
+
+    pub fn from_wave<W>(wave: &W, len: usize, scale: f64) -> Self
+    where
+        W: Wave<Output = Rgb>,
+    {
+        let colors = (0..len).map(|x| wave.wave(x as f64 * scale)).collect();
+        Self { colors }
+    }
+
+    pub fn colors(&self) -> &[Rgb] {
+        &self.colors
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    pub fn get(&self, index: u32) -> Rgb {
+        match self.colors.len() {
+            0 => Rgb::BLACK,
+            len => self.colors[index as usize % len],
+        }
+    }
+
+    /// Blends the two entries around a fractional `index`, wrapping past the
+    /// last entry back to the first.
+    pub fn get_smooth(&self, index: f64) -> Rgb {
+        let len = self.colors.len();
+        if len == 0 {
+            return Rgb::BLACK;
+        }
+        let index = index.rem_euclid(len as f64);
+        let floor = (index as usize).min(len - 1);
+        let frac = ((index - floor as f64) * BILINEAR_ONE as f64).round() as u32;
+        lerp_rgb(self.colors[floor], self.colors[(floor + 1) % len], frac)
+    }
+
+    /// View of the table rotated by `offset`, for palette cycling animations.
+    pub fn cycled(&self, offset: u32) -> CycledColorTable<'_> {
+        let offset = match self.colors.len() {
+            0 => 0,
+            len => offset as usize % len,
+        };
+        CycledColorTable {
+            table: self,
+            offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycledColorTable<'a> {
+    table: &'a ColorTable,
+    offset: usize,
+}
+
+impl CycledColorTable<'_> {
+    pub fn get(&self, index: u32) -> Rgb {
+        match self.table.colors.len() {
+            0 => Rgb::BLACK,
+            len => self.table.colors[(index as usize % len + self.offset) % len],
+        }
+    }
+}
+
+impl From<Vec<Rgb>> for ColorTable {
+    fn from(colors: Vec<Rgb>) -> Self {
+        Self::new(colors)
+    }
+}
+
+impl ColorMap for Palette {
+    fn color(&self, value: u8) -> Rgb {
+        self.get_color(value)
+    }
+}
+
+impl ColorMap for GradientPalette {
+    fn color(&self, value: u8) -> Rgb {
+        self.get_color(value)
+    }
+}
+
+impl ColorMap for ColorTable {
+    fn color(&self, value: u8) -> Rgb {
+        self.get(value as u32)
+    }
+}
+
+impl ColorMap for CycledColorTable<'_> {
+    fn color(&self, value: u8) -> Rgb {
+        self.get(value as u32)
+    }
+}
+
+impl FromStr for Palette {
+    type Err = ParsePaletteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s).ok_or_else(|| ParsePaletteError {
+            name: s.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePaletteError {
+    pub name: String,
+}
+
+impl Display for ParsePaletteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown palette {:?}", self.name)
+    }
+}
+
+impl Error for ParsePaletteError {}
+
 fn original(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 32 {
-        Rgb::new(num * 8, num * 8, 127 - num * 4)
+        rgb(num * 8, num * 8, 127 - num * 4)
     } else if num < 128 {
-        Rgb::new(255, 255 - (num - 32) * 8 / 3, (num - 32) * 4 / 3)
+        rgb(255, 255 - (num - 32) * 8 / 3, (num - 32) * 4 / 3)
     } else if num < 192 {
-        Rgb::new(
-            255 - (num - 128) * 4,
-            0 + (num - 128) * 3,
-            127 - (num - 128),
-        )
+        rgb(255 - (num - 128) * 4, (num - 128) * 3, 127 - (num - 128))
     } else {
-        Rgb::new(0, 192 - (num - 192) * 3, 64 + (num - 192))
+        rgb(0, 192 - (num - 192) * 3, 64 + (num - 192))
     }
 }
 
 fn fire(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 64 {
-        Rgb::new(num * 4, 0, 0)
+        rgb(num * 4, 0, 0)
     } else if num < 128 {
-        Rgb::new(255, (num - 64) * 2, 0)
+        rgb(255, (num - 64) * 2, 0)
     } else if num < 192 {
-        Rgb::new(255, 128 - ((num - 128) * 2), 0)
+        rgb(255, 128 - ((num - 128) * 2), 0)
     } else {
-        Rgb::new(255 - (num - 192) * 4, 0, 0)
+        rgb(255 - (num - 192) * 4, 0, 0)
     }
 }
 
 fn black_and_white(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 128 {
-        Rgb::new(255 - num * 2, 255 - num * 2, 255 - num * 2)
+        rgb(255 - num * 2, 255 - num * 2, 255 - num * 2)
     } else {
-        Rgb::new((num - 128) * 2, (num - 128) * 2, (num - 128) * 2)
+        rgb((num - 128) * 2, (num - 128) * 2, (num - 128) * 2)
     }
 }
 
 fn electric_blue(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 32 {
-        Rgb::new(0, 0, num * 4)
+        rgb(0, 0, num * 4)
     } else if num < 64 {
-        Rgb::new((num - 32) * 8, (num - 32) * 8, 127 + (num - 32) * 4)
+        rgb((num - 32) * 8, (num - 32) * 8, 127 + (num - 32) * 4)
     } else if num < 96 {
-        Rgb::new(
+        rgb(
             255 - (num - 64) * 8,
             255 - (num - 64) * 8,
             255 - (num - 64) * 4,
         )
     } else if num < 128 {
-        Rgb::new(0, 0, 127 - (num - 96) * 4)
+        rgb(0, 0, 127 - (num - 96) * 4)
     } else if num < 192 {
-        Rgb::new(0, 0, num - 128)
+        rgb(0, 0, num - 128)
     } else {
-        Rgb::new(0, 0, 63 - (num - 192))
+        rgb(0, 0, 63 - (num - 192))
     }
 }
 
@@ -290,32 +1136,33 @@ fn toon(num: u8) -> Rgb {
 }
 
 fn gold(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 32 {
-        Rgb::new(
+        rgb(
             54 + ((num) * (224 - 54) / 32),
             11 + ((num) * (115 - 11) / 32),
             2 + ((num) * (10 - 2) / 32),
         )
     } else if num < 64 {
-        Rgb::new(
+        rgb(
             224 + ((num - 32) * (255 - 224) / 32),
             115 + ((num - 32) * (192 - 115) / 32),
             10 + ((num - 32) * (49 - 10) / 32),
         )
     } else if num < 192 {
-        Rgb::new(
+        rgb(
             255,
             192 + ((num - 64) * (255 - 192) / 128),
             49 + ((num - 64) * (166 - 49) / 128),
         )
     } else if num < 224 {
-        Rgb::new(
+        rgb(
             255,
             255 - ((num - 192) * (255 - 192) / 32),
             166 - ((num - 192) * (166 - 49) / 32),
         )
     } else {
-        Rgb::new(
+        rgb(
             255 - ((num - 224) * (255 - 54) / 32),
             192 - ((num - 224) * (192 - 11) / 32),
             49 - ((num - 224) * (49 - 2) / 32),
@@ -356,37 +1203,40 @@ fn cga2(num: u8) -> Rgb {
 }
 
 fn primary_rgb(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 85 {
-        Rgb::new(255 - num * 3, 0 + num * 3, 0)
+        rgb(255 - num * 3, num * 3, 0)
     } else if num < 170 {
-        Rgb::new(0, 255 - (num - 85) * 3, 0 + (num - 85) * 3)
+        rgb(0, 255 - (num - 85) * 3, (num - 85) * 3)
     } else {
-        Rgb::new(0 + (num - 170) * 3, 0, 255 - (num - 170) * 3)
+        rgb((num - 170) * 3, 0, 255 - (num - 170) * 3)
     }
 }
 
 fn secondary_cmy(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 85 {
-        Rgb::new(0 + num * 3, 255 - num * 3, 255)
+        rgb(num * 3, 255 - num * 3, 255)
     } else if num < 170 {
-        Rgb::new(255, 0 + (num - 85) * 3, 255 - (num - 85) * 3)
+        rgb(255, (num - 85) * 3, 255 - (num - 85) * 3)
     } else {
-        Rgb::new(255 - (num - 170) * 3, 255, 0 + (num - 170) * 3)
+        rgb(255 - (num - 170) * 3, 255, (num - 170) * 3)
     }
 }
 
 fn tertiary1(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 85 {
-        Rgb::new(255 - num * 3 / 2, 127 - num * 3 / 2, 0 + num * 3)
+        rgb(255 - num * 3 / 2, 127 - num * 3 / 2, num * 3)
     } else if num < 170 {
-        Rgb::new(
+        rgb(
             127 - (num - 85) * 3 / 2,
-            0 + (num - 85) * 3,
+            (num - 85) * 3,
             255 - (num - 85) * 3 / 2,
         )
     } else {
-        Rgb::new(
-            0 + (num - 170) * 3,
+        rgb(
+            (num - 170) * 3,
             255 - (num - 170) * 3 / 2,
             127 - (num - 170) * 3 / 2,
         )
@@ -394,40 +1244,42 @@ fn tertiary1(num: u8) -> Rgb {
 }
 
 fn tertiary2(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 85 {
-        Rgb::new(255 - num * 3, 0 + num * 3 / 2, 127 + num * 3 / 2)
+        rgb(255 - num * 3, num * 3 / 2, 127 + num * 3 / 2)
     } else if num < 170 {
-        Rgb::new(
-            0 + (num - 85) * 3 / 2,
+        rgb(
+            (num - 85) * 3 / 2,
             127 + (num - 85) * 3 / 2,
             255 - (num - 85) * 3,
         )
     } else {
-        Rgb::new(
+        rgb(
             127 + (num - 170) * 3 / 2,
             255 - (num - 170) * 3,
-            0 + (num - 170) * 3 / 2,
+            (num - 170) * 3 / 2,
         )
     }
 }
 
 fn neon(num: u8) -> Rgb {
+    let num = num as i32;
     if num < 32 {
-        Rgb::new(num * 4, 0, num * 8)
+        rgb(num * 4, 0, num * 8)
     } else if num < 64 {
-        Rgb::new(124 - (num - 32) * 4, 0, 248 - (num - 32) * 8)
+        rgb(124 - (num - 32) * 4, 0, 248 - (num - 32) * 8)
     } else if num < 96 {
-        Rgb::new((num - 64) * 8, (num - 64) * 4, 0)
+        rgb((num - 64) * 8, (num - 64) * 4, 0)
     } else if num < 128 {
-        Rgb::new(248 - (num - 96) * 8, 124 - (num - 96) * 4, 0)
+        rgb(248 - (num - 96) * 8, 124 - (num - 96) * 4, 0)
     } else if num < 160 {
-        Rgb::new(0, (num - 128) * 4, (num - 128) * 8)
+        rgb(0, (num - 128) * 4, (num - 128) * 8)
     } else if num < 192 {
-        Rgb::new(0, 124 - (num - 160) * 4, 248 - (num - 160) * 8)
+        rgb(0, 124 - (num - 160) * 4, 248 - (num - 160) * 8)
     } else if num < 224 {
-        Rgb::new((num - 192) * 4, (num - 192) * 8, (num - 192) * 4)
+        rgb((num - 192) * 4, (num - 192) * 8, (num - 192) * 4)
     } else {
-        Rgb::new(
+        rgb(
             124 - (num - 224) * 4,
             248 - (num - 224) * 8,
             124 - (num - 224) * 4,
@@ -435,6 +1287,12 @@ fn neon(num: u8) -> Rgb {
     }
 }
 
+/// Builds a color from channels computed in `i32`, clamping each to `u8`.
+fn rgb(r: i32, g: i32, b: i32) -> Rgb {
+    let channel = |value: i32| value.clamp(0, u8::MAX as i32) as u8;
+    Rgb::new(channel(r), channel(g), channel(b))
+}
+
 const VGA: &[u64] = &[
     0, 43520, 11141120, 11184640, 2852126720, 2852170240, 2857697280, 2863311360, 1431655680,
     1431699200, 1442796800, 1442840320, 4283782400, 4283825920, 4294923520, 4294967040, 0,
@@ -468,3 +1326,154 @@ const VGA: &[u64] = &[
     1010904064, 876686336, 809577472, 742468608, 742469632, 742470656, 742472704, 742473984,
     742146304, 741622016, 741359872, 0, 0, 0, 0, 0, 0, 0, 0,
 ];
+
+fn rainbow(num: u8) -> Rgb {
+    Hsv::new(num as f64 * 360.0 / 256.0, 1.0, 1.0).to_rgb()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Rgb, expected: Rgb) {
+        let channels = <[u8; 3]>::from(actual)
+            .into_iter()
+            .zip(<[u8; 3]>::from(expected));
+        for (actual, expected) in channels {
+            assert!(actual.abs_diff(expected) <= 1, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn hsv_round_trip() {
+        let named = [
+            Rgb::BLACK,
+            Rgb::WHITE,
+            Rgb::RED,
+            Rgb::GREEN,
+            Rgb::BLUE,
+            Rgb::CYAN,
+            Rgb::MAGENTA,
+            Rgb::YELLOW,
+            Rgb::GRAY,
+        ];
+        for color in named {
+            assert_eq!(color.to_hsv().to_rgb(), color);
+        }
+        let steps = (0..=255).step_by(15);
+        for r in steps.clone() {
+            for g in steps.clone() {
+                for b in steps.clone() {
+                    let color = Rgb::new(r, g, b);
+                    assert_close(color.to_hsv().to_rgb(), color);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_hex_colors() {
+        assert_eq!(Rgb::from_hex("#1A2b3C"), Ok(Rgb::new(0x1A, 0x2B, 0x3C)));
+        assert_eq!(Rgb::from_hex("1a2b3c"), Ok(Rgb::new(0x1A, 0x2B, 0x3C)));
+        assert_eq!(Rgb::from_hex("#f80"), Ok(Rgb::new(0xFF, 0x88, 0x00)));
+        assert_eq!("0F0".parse(), Ok(Rgb::GREEN));
+        assert_eq!(
+            Rgb::from_hex(&Rgb::new(1, 2, 3).to_hex()),
+            Ok(Rgb::new(1, 2, 3))
+        );
+    }
+
+    #[test]
+    fn parse_hex_errors() {
+        assert_eq!(Rgb::from_hex(""), Err(ParseRgbError::InvalidLength(0)));
+        assert_eq!(Rgb::from_hex("#"), Err(ParseRgbError::InvalidLength(0)));
+        assert_eq!(Rgb::from_hex("#1234"), Err(ParseRgbError::InvalidLength(4)));
+        assert_eq!(
+            Rgb::from_hex("1234567"),
+            Err(ParseRgbError::InvalidLength(7))
+        );
+        assert_eq!(
+            Rgb::from_hex("#12g456"),
+            Err(ParseRgbError::InvalidDigit('g'))
+        );
+        assert_eq!(
+            Rgb::from_hex("##123"),
+            Err(ParseRgbError::InvalidDigit('#'))
+        );
+        assert_eq!(
+            "12 456".parse::<Rgb>(),
+            Err(ParseRgbError::InvalidDigit(' '))
+        );
+    }
+
+    #[test]
+    fn box_blur_small_image() {
+        let mut image = RgbImage::new(3, 3);
+        image[(1, 1)] = Rgb::WHITE;
+        let blurred = image.box_blur();
+        assert_eq!(blurred[(1, 1)], Rgb::new(28, 28, 28));
+        assert!(blurred.values().all(|&color| color == Rgb::new(28, 28, 28)));
+
+        let uniform = RgbImage::from_fn(3, 3, |_, _| Rgb::new(200, 100, 50));
+        assert!(uniform.box_blur() == uniform);
+    }
+
+    fn table() -> ColorTable {
+        ColorTable::new(vec![
+            Rgb::RED,
+            Rgb::GREEN,
+            Rgb::BLUE,
+            Rgb::WHITE,
+            Rgb::BLACK,
+        ])
+    }
+
+    #[test]
+    fn cycled_rotates_the_table() {
+        let table = table();
+        let len = table.len() as u32;
+        for i in 0..2 * len {
+            assert_eq!(table.cycled(len).get(i), table.cycled(0).get(i));
+            assert_eq!(table.cycled(0).get(i), table.get(i));
+            assert_eq!(table.cycled(1).get(i), table.get(i + 1));
+        }
+        assert_eq!(ColorTable::default().cycled(3).get(1), Rgb::BLACK);
+    }
+
+    // Overflow checks are only on in debug builds.
+    #[cfg(debug_assertions)]
+    #[test]
+    fn palettes_accept_every_value() {
+        for palette in Palette::all() {
+            for value in 0..=u8::MAX {
+                palette.get_color(value);
+            }
+        }
+    }
+
+    #[test]
+    fn get_smooth_matches_get_and_wraps() {
+        let table = table();
+        let len = table.len() as u32;
+        for i in 0..2 * len {
+            assert_eq!(table.get_smooth(i as f64), table.get(i));
+        }
+        let wrapped = table.get_smooth(len as f64 - 0.5);
+        assert!((127..=128).contains(&wrapped.r) && wrapped.g == 0 && wrapped.b == 0);
+        assert_eq!(table.get_smooth(-0.5), wrapped);
+    }
+
+    #[test]
+    fn averages_round_to_nearest() {
+        assert_eq!(
+            Rgb::average([Rgb::BLACK, Rgb::WHITE]),
+            Rgb::new(128, 128, 128)
+        );
+        let colors = [Rgb::new(0, 1, 2), Rgb::new(1, 2, 2), Rgb::new(1, 2, 3)];
+        assert_eq!(Rgb::average(colors), Rgb::new(1, 2, 2));
+        let colors = [Rgba::new(255, 0, 0, 255), Rgba::new(0, 0, 0, 0)];
+        assert_eq!(Rgba::average(colors), Rgba::new(255, 0, 0, 128));
+        let colors = [Rgba::new(255, 0, 0, 1), Rgba::new(0, 0, 255, 2)];
+        assert_eq!(Rgba::average(colors), Rgba::new(85, 0, 170, 2));
+    }
+}
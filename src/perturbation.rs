@@ -0,0 +1,106 @@
+use num::complex::Complex64;
+
+use crate::{
+    high_precision::{ComplexHP, PositionHP},
+    mandelbrot::{
+        build_iteration_matrix_with, par_build_iteration_matrix_with, BuildMandelbrotSetOptions,
+        Iteration, IterationMatrix, ParallelBuildMandelbrotSetOptions, Position,
+    },
+    point::Point,
+    utils::PipelineResult,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerturbationRenderer {
+    pos: PositionHP,
+    orbit: Vec<Complex64>,
+}
+
+impl PerturbationRenderer {
+    pub fn new(pos: PositionHP) -> Self {
+        let orbit = reference_orbit(&pos.as_complex(), pos.limit);
+        Self { pos, orbit }
+    }
+
+    pub fn pos(&self) -> &PositionHP {
+        &self.pos
+    }
+
+    pub fn reference_orbit(&self) -> &[Complex64] {
+        &self.orbit
+    }
+
+    pub fn compute_iterations(&self, offset_scale: Point<f64>) -> Iteration {
+        let delta_c = Complex64::from(offset_scale / self.pos.zoom);
+        self.compute_delta_iterations(delta_c)
+    }
+
+    pub fn compute_delta_iterations(&self, delta_c: Complex64) -> Iteration {
+        let orbit = &self.orbit;
+        let mut delta_z = delta_c;
+        let mut ref_index = 1;
+        for i in 0..self.pos.limit {
+            let z = orbit[ref_index] + delta_z;
+            if z.norm_sqr() > 4.0 {
+                return Iteration::Finite(i);
+            }
+            if ref_index + 1 == orbit.len() || z.norm_sqr() < delta_z.norm_sqr() {
+                delta_z = z;
+                ref_index = 0;
+            }
+            delta_z = 2.0 * orbit[ref_index] * delta_z + delta_z * delta_z + delta_c;
+            ref_index += 1;
+        }
+        Iteration::Infinite
+    }
+
+    pub fn render(&self, matrix: &mut IterationMatrix, options: BuildMandelbrotSetOptions) {
+        let delta_pos = self.delta_position();
+        build_iteration_matrix_with(
+            matrix,
+            &delta_pos,
+            |delta_c| self.compute_delta_iterations(delta_c),
+            |iter| iter,
+            options,
+        )
+    }
+
+    pub fn par_render(
+        &self,
+        matrix: &mut IterationMatrix,
+        options: ParallelBuildMandelbrotSetOptions,
+    ) -> PipelineResult<()> {
+        let delta_pos = self.delta_position();
+        par_build_iteration_matrix_with(
+            matrix,
+            &delta_pos,
+            |delta_c| self.compute_delta_iterations(delta_c),
+            |iter| iter,
+            options,
+        )
+    }
+
+    fn delta_position(&self) -> Position {
+        Position::new(Point::default(), self.pos.zoom, self.pos.limit)
+    }
+}
+
+fn reference_orbit(c: &ComplexHP, limit: u32) -> Vec<Complex64> {
+    let mut orbit = Vec::with_capacity(limit as usize + 1);
+    orbit.push(Complex64::default());
+    let mut z_re = c.re.clone();
+    let mut z_im = c.im.clone();
+    for _ in 0..limit {
+        let z = Complex64::new(z_re.to_f64(), z_im.to_f64());
+        orbit.push(z);
+        if z.norm_sqr() > 4.0 {
+            break;
+        }
+        let sq_re = &z_re * &z_re;
+        let sq_im = &z_im * &z_im;
+        let re_im = &z_re * &z_im;
+        z_im = &(&re_im + &re_im) + &c.im;
+        z_re = &(&sq_re - &sq_im) + &c.re;
+    }
+    orbit
+}
@@ -2,7 +2,10 @@ use std::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+use num::Float;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point<T> {
     pub x: T,
     pub y: T,
@@ -30,15 +33,103 @@ impl<T> Point<T> {
     }
 }
 
+/// Primitive numbers usable in the geometric methods of [`Point`]. Sealed, so
+/// the bounds can grow without breaking downstream code.
+pub trait Scalar:
+    private::Sealed
+    + Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl private::Sealed for $t {}
+            impl Scalar for $t {}
+        )*
+    };
+}
+
+impl_scalar!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl<T> Point<T>
+where
+    T: Scalar,
+{
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length_squared(self) -> T {
+        self.dot(self)
+    }
+}
+
+impl<T> Point<T>
+where
+    T: Scalar + Float + Into<f64>,
+{
+    pub fn length(self) -> f64 {
+        self.length_squared().into().sqrt()
+    }
+
+    pub fn distance(self, other: Self) -> f64 {
+        (self - other).length()
+    }
+}
+
+impl Point<f64> {
+    /// Rotates counterclockwise around the origin.
+    pub fn rotate(self, radians: f64) -> Point<f64> {
+        let (sin, cos) = radians.sin_cos();
+        Point::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+}
+
+pub trait Lerp {
+    fn lerp(self, to: Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+    fn lerp(self, to: Self, t: f64) -> Self {
+        self * (1.0 - t) + to * t
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(self, to: Self, t: f64) -> Self {
+        let t = t as f32;
+        self * (1.0 - t) + to * t
+    }
+}
+
+impl<T> Lerp for Point<T>
+where
+    T: Lerp,
+{
+    fn lerp(self, to: Self, t: f64) -> Self {
+        Self::new(self.x.lerp(to.x, t), self.y.lerp(to.y, t))
+    }
+}
+
 impl<T> From<(T, T)> for Point<T> {
     fn from((x, y): (T, T)) -> Self {
         Self::new(x, y)
     }
 }
 
-impl<T> Into<(T, T)> for Point<T> {
-    fn into(self) -> (T, T) {
-        (self.x, self.y)
+impl<T> From<Point<T>> for (T, T) {
+    fn from(val: Point<T>) -> Self {
+        (val.x, val.y)
     }
 }
 
@@ -267,3 +358,22 @@ where
         *self %= Self::splat(rhs);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lerp_ends_and_midpoint() {
+        assert_eq!(2.0f64.lerp(6.0, 0.0), 2.0);
+        assert_eq!(2.0f64.lerp(6.0, 1.0), 6.0);
+        assert_eq!(2.0f64.lerp(6.0, 0.5), 4.0);
+        assert_eq!((-1.5f32).lerp(0.5, 0.0), -1.5);
+        assert_eq!((-1.5f32).lerp(0.5, 1.0), 0.5);
+        assert_eq!((-1.5f32).lerp(0.5, 0.5), -0.5);
+        let (from, to) = (Point::new(1.0, -2.0), Point::new(3.0, 4.0));
+        assert_eq!(from.lerp(to, 0.0), from);
+        assert_eq!(from.lerp(to, 1.0), to);
+        assert_eq!(from.lerp(to, 0.5), Point::new(2.0, 1.0));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{mandelbrot::Position, point::Point, utils::normalize_name};
+
+/// A gallery of well-known locations in the Mandelbrot set.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Positions {
+    /// Overview of the whole set.
+    #[default]
+    Home,
+    /// Flower-like minibrot near the tip of the main antenna.
+    Flower,
+    /// Island minibrot deep in the main antenna.
+    JuliaIsland,
+    /// Spirals near the lower period-3 bulb.
+    Valley,
+    /// Spirals in seahorse valley, between the main cardioid and the period-2 bulb.
+    Elephant,
+    /// Starfish-shaped spirals on the upper edge of the main cardioid.
+    Starfish,
+    /// Radiating pattern deep in seahorse valley.
+    Sun,
+    /// Tendrils at the top of the set.
+    Tendris,
+    /// Branching structure on the main antenna.
+    Tree,
+    /// Extremely deep zoom on the main antenna, near the f64 precision limit.
+    Unnamed,
+}
+
+const HOME: Position = Position::new(Point::new(-1.34228, 0.0), 300.0, 200);
+const FLOWER: Position = Position::new(Point::new(-1.9999858812, 0.0), 2000000000000.0, 600);
+const JULIA_ISLAND: Position =
+    Position::new(Point::new(-1.768778828, -0.001738910), 1585714676.0, 1200);
+const VALLEY: Position = Position::new(Point::new(-0.071875677, -0.649981301), 203212.0, 1200);
+const ELEPHANT: Position = Position::new(Point::new(-0.743517833, -0.127094578), 113388.0, 600);
+const STARFISH: Position = Position::new(Point::new(-0.374004139, 0.659792175), 484254.0, 400);
+const SUN: Position = Position::new(Point::new(-0.776592852, -0.13664085), 58282440.0, 600);
+const TENDRIS: Position = Position::new(Point::new(-0.226266647, 1.11617444), 743786806.0, 1200);
+const TREE: Position = Position::new(Point::new(-1.940157342, -0.00000085), 600000000.0, 400);
+const UNNAMED: Position = Position::new(
+    Point::new(-1.88488933694469, 0.00000000081387),
+    1900000000000000.0,
+    1200,
+);
+
+const ALL: [Positions; 10] = [
+    Positions::Home,
+    Positions::Flower,
+    Positions::JuliaIsland,
+    Positions::Valley,
+    Positions::Elephant,
+    Positions::Starfish,
+    Positions::Sun,
+    Positions::Tendris,
+    Positions::Tree,
+    Positions::Unnamed,
+];
+
+impl Positions {
+    pub fn all() -> impl Iterator<Item = Positions> {
+        ALL.into_iter()
+    }
+
+    pub fn from_name(name: &str) -> Option<Positions> {
+        let name = normalize_name(name);
+        Self::all().find(|pos| normalize_name(pos.name()) == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Positions::Home => "Home",
+            Positions::Flower => "Flower",
+            Positions::JuliaIsland => "JuliaIsland",
+            Positions::Valley => "Valley",
+            Positions::Elephant => "Elephant",
+            Positions::Starfish => "Starfish",
+            Positions::Sun => "Sun",
+            Positions::Tendris => "Tendris",
+            Positions::Tree => "Tree",
+            Positions::Unnamed => "Unnamed",
+        }
+    }
+
+    pub fn pos(&self) -> &'static Position {
+        match self {
+            Positions::Home => &HOME,
+            Positions::Flower => &FLOWER,
+            Positions::JuliaIsland => &JULIA_ISLAND,
+            Positions::Valley => &VALLEY,
+            Positions::Elephant => &ELEPHANT,
+            Positions::Starfish => &STARFISH,
+            Positions::Sun => &SUN,
+            Positions::Tendris => &TENDRIS,
+            Positions::Tree => &TREE,
+            Positions::Unnamed => &UNNAMED,
+        }
+    }
+}
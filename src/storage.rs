@@ -0,0 +1,275 @@
+use std::io::{self, ErrorKind, Read, Write};
+
+use crate::{
+    mandelbrot::{Iteration, IterationMatrix},
+    paint::{Rgb, RgbImage},
+};
+
+const MAGIC: &[u8; 4] = b"MBIM";
+const VERSION: u8 = 1;
+
+impl IterationMatrix {
+    /// Writes the matrix as `MBIM`, a version byte, little-endian width and
+    /// height, then one LEB128 varint per cell: `0` for `Infinite`, `n + 1`
+    /// for `Finite(n)`.
+    pub fn write_to<W>(&self, mut writer: W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.width().to_le_bytes())?;
+        writer.write_all(&self.height().to_le_bytes())?;
+        for iter in self.values() {
+            let value = match *iter {
+                Iteration::Finite(iter) => iter as u64 + 1,
+                Iteration::Infinite => 0,
+            };
+            write_varint(&mut writer, value)?;
+        }
+        writer.flush()
+    }
+
+    pub fn read_from<R>(mut reader: R) -> io::Result<IterationMatrix>
+    where
+        R: Read,
+    {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not an iteration matrix"));
+        }
+        let mut version = [0; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != VERSION {
+            return Err(invalid_data("unsupported iteration matrix version"));
+        }
+        let width = read_u32(&mut reader)?;
+        let height = read_u32(&mut reader)?;
+        let len = width as usize * height as usize;
+        let mut data = Vec::with_capacity(len.min(1 << 20));
+        for _ in 0..len {
+            let iter = match read_varint(&mut reader)? {
+                0 => Iteration::Infinite,
+                value => u32::try_from(value - 1)
+                    .map(Iteration::Finite)
+                    .map_err(|_| invalid_data("iteration count out of range"))?,
+            };
+            data.push(iter);
+        }
+        Ok(IterationMatrix::try_from_raw(width, height, data).unwrap())
+    }
+
+    /// Writes a binary PGM (`P5`), scaling iterations so that `max_limit` is
+    /// white. `Infinite` cells are black.
+    pub fn write_pgm<W>(&self, mut writer: W, max_limit: u32) -> io::Result<()>
+    where
+        W: Write,
+    {
+        write!(writer, "P5\n{} {}\n255\n", self.width(), self.height())?;
+        let max_limit = max_limit.max(1) as u64;
+        let data: Vec<_> = self
+            .values()
+            .map(|iter| match *iter {
+                Iteration::Finite(iter) => (iter as u64 * 255 / max_limit).min(255) as u8,
+                Iteration::Infinite => 0,
+            })
+            .collect();
+        writer.write_all(&data)?;
+        writer.flush()
+    }
+}
+
+impl RgbImage {
+    /// Writes a binary PPM (`P6`).
+    pub fn write_ppm<W>(&self, mut writer: W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        write!(writer, "P6\n{} {}\n255\n", self.width(), self.height())?;
+        let data: Vec<_> = self
+            .values()
+            .flat_map(|&color| <[u8; 3]>::from(color))
+            .collect();
+        writer.write_all(&data)?;
+        writer.flush()
+    }
+
+    /// Reads a binary PPM (`P6`) with a maximum value of 255.
+    pub fn read_ppm<R>(mut reader: R) -> io::Result<RgbImage>
+    where
+        R: Read,
+    {
+        if read_header_token(&mut reader)? != "P6" {
+            return Err(invalid_data("not a binary PPM"));
+        }
+        let width = parse_header_number(&mut reader)?;
+        let height = parse_header_number(&mut reader)?;
+        if parse_header_number(&mut reader)? != 255 {
+            return Err(invalid_data("unsupported PPM maximum value"));
+        }
+        let len = width as usize * height as usize;
+        let mut data = Vec::with_capacity(len.min(1 << 20));
+        for _ in 0..len {
+            let mut color = [0; 3];
+            reader.read_exact(&mut color)?;
+            data.push(Rgb::from(color));
+        }
+        Ok(RgbImage::try_from_raw(width, height, data).unwrap())
+    }
+}
+
+/// Reads a whitespace-separated header token, skipping `#` comments, and
+/// consumes the single whitespace byte following it.
+fn read_header_token<R>(reader: &mut R) -> io::Result<String>
+where
+    R: Read,
+{
+    let mut token = String::new();
+    let mut in_comment = false;
+    loop {
+        let mut byte = [0; 1];
+        reader.read_exact(&mut byte)?;
+        match byte[0] {
+            b'\n' if in_comment => in_comment = false,
+            _ if in_comment => {}
+            b'#' if token.is_empty() => in_comment = true,
+            byte if byte.is_ascii_whitespace() => {
+                if !token.is_empty() {
+                    return Ok(token);
+                }
+            }
+            byte => token.push(byte as char),
+        }
+    }
+}
+
+fn parse_header_number<R>(reader: &mut R) -> io::Result<u32>
+where
+    R: Read,
+{
+    read_header_token(reader)?
+        .parse()
+        .map_err(|_| invalid_data("invalid PPM header"))
+}
+
+fn write_varint<W>(writer: &mut W, mut value: u64) -> io::Result<()>
+where
+    W: Write,
+{
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R>(reader: &mut R) -> io::Result<u64>
+where
+    R: Read,
+{
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0; 1];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7F) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("varint is too long"))
+}
+
+fn read_u32<R>(reader: &mut R) -> io::Result<u32>
+where
+    R: Read,
+{
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mandelbrot::{MandelbrotSetImage, Position},
+        point::Point,
+    };
+
+    fn rendered() -> IterationMatrix {
+        let mut matrix = IterationMatrix::new(13, 7);
+        let pos = Position::new(Point::new(-0.75, 0.0), 6.0, 50);
+        matrix.build_image(&pos, |iter| iter, Default::default());
+        matrix
+    }
+
+    #[test]
+    fn iteration_matrix_round_trip() {
+        let matrix = rendered();
+        assert!(matrix.values().any(Iteration::is_infinite));
+        assert!(matrix.values().any(|iter| !iter.is_infinite()));
+        let mut bytes = Vec::new();
+        matrix.write_to(&mut bytes).unwrap();
+        assert_eq!(&bytes[..4], MAGIC);
+        assert!(IterationMatrix::read_from(bytes.as_slice()).unwrap() == matrix);
+    }
+
+    #[test]
+    fn read_from_rejects_bad_magic_and_version() {
+        let mut bytes = Vec::new();
+        rendered().write_to(&mut bytes).unwrap();
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        let err = IterationMatrix::read_from(bad_magic.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let mut bad_version = bytes;
+        bad_version[4] = VERSION + 1;
+        let err = IterationMatrix::read_from(bad_version.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn ppm_round_trip() {
+        let data = (0..5 * 3)
+            .map(|i| Rgb::new(i as u8 * 17, 255 - i as u8, i as u8 % 4 * 60))
+            .collect();
+        let image = RgbImage::try_from_raw(5, 3, data).unwrap();
+        let mut bytes = Vec::new();
+        image.write_ppm(&mut bytes).unwrap();
+        assert!(bytes.starts_with(b"P6\n5 3\n255\n"));
+        assert!(RgbImage::read_ppm(bytes.as_slice()).unwrap() == image);
+    }
+
+    #[test]
+    fn read_ppm_skips_header_comments() {
+        let mut bytes = b"P6\n# made by hand\n2 1\n255\n".to_vec();
+        bytes.extend([1, 2, 3, 4, 5, 6]);
+        let image = RgbImage::read_ppm(bytes.as_slice()).unwrap();
+        let expected = vec![Rgb::new(1, 2, 3), Rgb::new(4, 5, 6)];
+        assert!(image == RgbImage::try_from_raw(2, 1, expected).unwrap());
+    }
+
+    #[test]
+    fn pgm_header_and_size() {
+        let matrix = rendered();
+        let mut bytes = Vec::new();
+        matrix.write_pgm(&mut bytes, 50).unwrap();
+        let header = b"P5\n13 7\n255\n";
+        assert!(bytes.starts_with(header));
+        assert_eq!(bytes.len(), header.len() + 13 * 7);
+        for (&byte, iter) in bytes[header.len()..].iter().zip(matrix.values()) {
+            match *iter {
+                Iteration::Finite(iter) => assert_eq!(byte, (iter * 255 / 50).min(255) as u8),
+                Iteration::Infinite => assert_eq!(byte, 0),
+            }
+        }
+    }
+}
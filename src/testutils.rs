@@ -0,0 +1,48 @@
+//! Straightforward reference implementations to check optimized code against.
+
+use std::f64::consts::LN_2;
+
+use num::complex::Complex64;
+
+use crate::mandelbrot::{Iteration, SmoothIteration};
+
+const ESCAPE_RADIUS_SQ: f64 = 4.0;
+
+/// The naive escape-time loop, without interior shortcuts or period checks.
+pub fn reference_escape(c: Complex64, limit: u32) -> Iteration {
+    let (iter, _) = reference_escape_with_z(c, limit);
+    iter
+}
+
+pub fn reference_escape_with_z(c: Complex64, limit: u32) -> (Iteration, Complex64) {
+    let mut z = c;
+    for i in 0..limit {
+        if z.norm_sqr() > ESCAPE_RADIUS_SQ {
+            return (Iteration::Finite(i), z);
+        }
+        z = z * z + c;
+    }
+    (Iteration::Infinite, z)
+}
+
+pub fn reference_multibrot_escape(c: Complex64, limit: u32, power: u32) -> Iteration {
+    let mut z = c;
+    for i in 0..limit {
+        if z.norm_sqr() > ESCAPE_RADIUS_SQ {
+            return Iteration::Finite(i);
+        }
+        z = (0..power).fold(Complex64::new(1.0, 0.0), |acc, _| acc * z) + c;
+    }
+    Iteration::Infinite
+}
+
+pub fn reference_smooth_escape(c: Complex64, limit: u32) -> SmoothIteration {
+    match reference_escape_with_z(c, limit) {
+        (Iteration::Finite(i), z) => {
+            let log_norm = z.norm_sqr().ln() / 2.0;
+            let nu = log_norm.ln() / LN_2;
+            SmoothIteration::Finite(i as f64 + 1.0 - nu)
+        }
+        (Iteration::Infinite, _) => SmoothIteration::Infinite,
+    }
+}
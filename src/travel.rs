@@ -0,0 +1,184 @@
+use crossbeam::channel::Receiver;
+
+use crate::{
+    mandelbrot::{
+        Iteration, ParallelBuildMandelbrotSetOptions, ParallelMandelbrotSetImage, Position,
+    },
+    paint::{Rgb, RgbImage},
+    point::Lerp,
+    utils::PipelineResult,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn ease(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+
+    /// Derivative of [`Easing::ease`], i.e. the relative speed at progress `t`.
+    pub fn velocity(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => 1.0,
+            Easing::EaseIn => 2.0 * t,
+            Easing::EaseOut => 2.0 * (1.0 - t),
+            Easing::EaseInOut => 6.0 * t * (1.0 - t),
+        }
+    }
+}
+
+/// Frame-by-frame animation through an ordered list of keyframes.
+///
+/// Easing is applied to the whole path, so the motion only speeds up at the
+/// first keyframe and slows down at the last one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Travel {
+    keyframes: Vec<Position>,
+    frames_per_segment: u32,
+    easing: Easing,
+    frame: u32,
+}
+
+impl Travel {
+    pub fn new(keyframes: Vec<Position>, frames_per_segment: u32) -> Self {
+        Self {
+            keyframes,
+            frames_per_segment: frames_per_segment.max(1),
+            easing: Easing::default(),
+            frame: 0,
+        }
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn keyframes(&self) -> &[Position] {
+        &self.keyframes
+    }
+
+    pub fn len(&self) -> u32 {
+        match self.keyframes.len() {
+            0 => 0,
+            len => (len as u32 - 1) * self.frames_per_segment + 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    pub fn reset(&mut self) {
+        self.frame = 0;
+    }
+
+    pub fn next_frame(&mut self) -> Option<Position> {
+        let len = self.len();
+        if self.frame >= len {
+            return None;
+        }
+        let segments = len - 1;
+        let pos = if segments == 0 {
+            self.keyframes[0].clone()
+        } else {
+            let t = self.easing.ease(self.frame as f64 / segments as f64);
+            let progress = t * (self.keyframes.len() - 1) as f64;
+            let index = (progress as usize).min(self.keyframes.len() - 2);
+            interpolate(
+                &self.keyframes[index],
+                &self.keyframes[index + 1],
+                progress - index as f64,
+            )
+        };
+        self.frame += 1;
+        Some(pos)
+    }
+}
+
+impl Iterator for Travel {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame()
+    }
+}
+
+fn interpolate(from: &Position, to: &Position, t: f64) -> Position {
+    let zoom = from.zoom * (to.zoom / from.zoom).powf(t);
+    // Move the point proportionally to the change of the visible extent, so
+    // the target stays on screen while zooming in and out.
+    let weight = if from.zoom == to.zoom {
+        t
+    } else {
+        (from.zoom.recip() - zoom.recip()) / (from.zoom.recip() - to.zoom.recip())
+    };
+    let point = from.point.lerp(to.point, weight);
+    let limit = (from.limit as f64).lerp(to.limit as f64, t);
+    let rotation = from.rotation.lerp(to.rotation, t);
+    Position::new(point, zoom, limit.round() as u32).rotation(rotation)
+}
+
+/// Frames rendered by [`render_frames`], in the order of their positions.
+#[derive(Debug)]
+pub struct Frames(Receiver<RgbImage>);
+
+impl Iterator for Frames {
+    type Item = RgbImage;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.recv().ok()
+    }
+}
+
+/// Renders a frame for every position on a background thread while `action`
+/// consumes the previous ones, so encoding overlaps with computing. Rendering
+/// stops early once `action` drops the [`Frames`].
+pub fn render_frames<I, F, A, R>(
+    positions: I,
+    width: u32,
+    height: u32,
+    paint: F,
+    options: ParallelBuildMandelbrotSetOptions,
+    action: A,
+) -> PipelineResult<R>
+where
+    I: IntoIterator<Item = Position>,
+    I::IntoIter: Send,
+    F: FnMut(Iteration) -> Rgb + Send + Clone,
+    A: FnOnce(Frames) -> R,
+{
+    let positions = positions.into_iter();
+    let (frame_snd, frame_recv) = crossbeam::channel::bounded(1);
+    crossbeam::scope(move |s| {
+        let renderer = s.spawn(move |_| {
+            for pos in positions {
+                let mut image = RgbImage::new(width, height);
+                image.par_build_image(&pos, paint.clone(), options.clone())?;
+                if frame_snd.send(image).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        });
+        let result = action(Frames(frame_recv));
+        renderer
+            .join()
+            .and_then(|rendered| rendered.map(|_| result))
+    })
+    .and_then(|result| result)
+}
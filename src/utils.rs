@@ -1,3 +1,11 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
 pub trait CrossJoin
 where
     Self: Iterator + Sized,
@@ -9,6 +17,22 @@ where
     {
         self.flat_map(move |item| std::iter::repeat(item).zip(other.clone()))
     }
+
+    /// Like [`CrossJoin::cross_join`], but collects `other` once up front
+    /// instead of cloning the iterator for every outer item. Worth it when the
+    /// inner sequence is expensive to regenerate, at the cost of holding all
+    /// of it in memory.
+    fn cross_join_buffered<U>(self, other: U) -> impl Iterator<Item = (Self::Item, U::Item)>
+    where
+        U: IntoIterator,
+        U::Item: Clone,
+    {
+        let inner: Arc<[U::Item]> = other.into_iter().collect();
+        self.flat_map(move |item| {
+            let inner = inner.clone();
+            (0..inner.len()).map(move |index| (item.clone(), inner[index].clone()))
+        })
+    }
 }
 
 impl<T> CrossJoin for T
@@ -64,11 +88,80 @@ pub type PipelineError = Box<dyn std::any::Any + Send>;
 
 pub type PipelineResult<T> = Result<T, PipelineError>;
 
+#[derive(Clone)]
+pub struct Progress(Arc<dyn Fn(f32) + Send + Sync>);
+
+impl Progress {
+    pub fn new<F>(report: F) -> Self
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        Self(Arc::new(report))
+    }
+
+    pub fn report(&self, fraction: f32) {
+        (self.0)(fraction)
+    }
+}
+
+impl Debug for Progress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Progress").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Progress {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// Worker count used by the parallel builders when `workers` is not set: one
+/// less than the number of CPUs, leaving a core for feeding and collecting.
+pub fn recommended_workers() -> u32 {
+    (num_cpus::get() as u32).saturating_sub(1).max(1)
+}
+
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(not(feature = "rayon"))]
 pub(crate) fn pipeline<T, U, R, I, F, A>(
     items: I,
     map: F,
     mut action: A,
     workers: Option<u32>,
+    cancel: Option<CancelToken>,
 ) -> PipelineResult<R>
 where
     T: Send,
@@ -78,33 +171,220 @@ where
     A: FnMut(crossbeam::channel::Receiver<U>) -> R,
 {
     let workers = workers
-        .map(|v| v as usize)
-        .unwrap_or_else(|| num_cpus::get())
-        .saturating_sub(1)
-        .max(1);
+        .map(|v| v.max(1))
+        .unwrap_or_else(recommended_workers) as usize;
     let channel_cap = workers * 2;
     let (item_snd, item_recv) = crossbeam::channel::bounded(channel_cap);
     let (result_snd, result_recv) = crossbeam::channel::bounded(channel_cap);
+    let is_cancelled = {
+        let cancel = cancel.clone();
+        move || cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+    };
     let result = crossbeam::scope(move |s| {
-        s.spawn(move |_| {
+        let feeder_is_cancelled = is_cancelled.clone();
+        let feeder = s.spawn(move |_| {
             for item in items {
-                item_snd.send(item).unwrap();
+                if feeder_is_cancelled() || item_snd.send(item).is_err() {
+                    break;
+                }
             }
         });
-        for _ in 0..workers {
-            let item_recv = item_recv.clone();
-            let result_snd = result_snd.clone();
-            let mut map = map.clone();
-            s.spawn(move |_| {
-                for item in item_recv {
-                    let result = map(item);
-                    result_snd.send(result).unwrap();
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let item_recv = item_recv.clone();
+                let result_snd = result_snd.clone();
+                let mut map = map.clone();
+                let is_cancelled = is_cancelled.clone();
+                s.spawn(move |_| {
+                    for item in item_recv {
+                        if is_cancelled() {
+                            break;
+                        }
+                        let result = map(item);
+                        if result_snd.send(result).is_err() {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(item_recv);
+        drop(result_snd);
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || action(result_recv)));
+        let joined = std::iter::once(feeder)
+            .chain(handles)
+            .map(|handle| handle.join())
+            .fold(Ok(()), Result::and);
+        result.and_then(|result| joined.map(|_| result))
+    })
+    .and_then(|result| result);
+    match cancel {
+        Some(cancel) if cancel.is_cancelled() => Err(Box::new(Cancelled)),
+        _ => result,
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn par_map_chunks<T, U, F>(
+    data: &[T],
+    chunk_len: usize,
+    mut map: F,
+    workers: Option<u32>,
+) -> PipelineResult<Vec<U>>
+where
+    T: Sync,
+    U: Send,
+    F: FnMut(&T) -> U + Send + Clone,
+{
+    let chunks = data.chunks(chunk_len.max(1));
+    let mut results: Vec<Option<Vec<U>>> =
+        std::iter::repeat_with(|| None).take(chunks.len()).collect();
+    pipeline(
+        chunks.enumerate(),
+        move |(index, chunk)| (index, chunk.iter().map(&mut map).collect::<Vec<_>>()),
+        |recv| {
+            for (index, result) in recv {
+                results[index] = Some(result);
+            }
+        },
+        workers,
+        None,
+    )?;
+    Ok(results.into_iter().flatten().flatten().collect())
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn par_map_chunks<T, U, F>(
+    data: &[T],
+    chunk_len: usize,
+    map: F,
+    workers: Option<u32>,
+) -> PipelineResult<Vec<U>>
+where
+    T: Sync,
+    U: Send,
+    F: FnMut(&T) -> U + Send + Clone,
+{
+    use rayon::prelude::*;
+    use std::sync::Mutex;
+
+    let template = Mutex::new(map);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers.unwrap_or(0) as usize)
+        .build()
+        .map_err(|err| Box::new(err) as PipelineError)?;
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pool.install(|| {
+            data.par_chunks(chunk_len.max(1))
+                .map_init(
+                    || template.lock().unwrap().clone(),
+                    |map, chunk| chunk.iter().map(map).collect::<Vec<_>>(),
+                )
+                .flatten_iter()
+                .collect()
+        })
+    }))
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn par_for_each_chunk_mut<T, F>(
+    data: &mut [T],
+    chunk_len: usize,
+    f: F,
+    workers: Option<u32>,
+) -> PipelineResult<()>
+where
+    T: Send,
+    F: Fn(usize, &mut T) + Sync,
+{
+    let chunk_len = chunk_len.max(1);
+    let f = &f;
+    pipeline(
+        data.chunks_mut(chunk_len).enumerate(),
+        move |(index, chunk)| {
+            let start = index * chunk_len;
+            for (offset, value) in chunk.iter_mut().enumerate() {
+                f(start + offset, value);
+            }
+        },
+        |recv| recv.into_iter().for_each(drop),
+        workers,
+        None,
+    )
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn par_for_each_chunk_mut<T, F>(
+    data: &mut [T],
+    chunk_len: usize,
+    f: F,
+    workers: Option<u32>,
+) -> PipelineResult<()>
+where
+    T: Send,
+    F: Fn(usize, &mut T) + Sync,
+{
+    use rayon::prelude::*;
+
+    let chunk_len = chunk_len.max(1);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers.unwrap_or(0) as usize)
+        .build()
+        .map_err(|err| Box::new(err) as PipelineError)?;
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pool.install(|| {
+            data.par_chunks_mut(chunk_len)
+                .enumerate()
+                .for_each(|(index, chunk)| {
+                    let start = index * chunk_len;
+                    for (offset, value) in chunk.iter_mut().enumerate() {
+                        f(start + offset, value);
+                    }
+                })
+        })
+    }))
+}
+
+#[cfg(all(test, not(feature = "rayon")))]
+mod tests {
+    use std::{
+        collections::HashSet,
+        sync::{Barrier, Mutex},
+        thread::{self, ThreadId},
+    };
+
+    use super::*;
+
+    #[test]
+    fn pipeline_uses_requested_worker_count() {
+        for workers in [1, 3, 4] {
+            let barrier = Arc::new(Barrier::new(workers as usize));
+            let threads = Arc::new(Mutex::new(HashSet::<ThreadId>::new()));
+            let map = {
+                let threads = threads.clone();
+                move |item: u32| {
+                    // Each worker blocks on its first item until all of them hold one,
+                    // so fewer threads than requested would never finish.
+                    if item < workers {
+                        barrier.wait();
+                    }
+                    threads.lock().unwrap().insert(thread::current().id());
+                    item * 2
                 }
-            });
+            };
+            let sum = pipeline(
+                0..64,
+                map,
+                |results| results.into_iter().sum::<u32>(),
+                Some(workers),
+                None,
+            )
+            .unwrap();
+            assert_eq!(sum, (0..64).map(|item| item * 2).sum());
+            let threads = threads.lock().unwrap();
+            assert_eq!(threads.len(), workers as usize);
+            assert!(!threads.contains(&thread::current().id()));
         }
-        drop(result_snd);
-        let result = action(result_recv);
-        result
-    });
-    result
+    }
 }